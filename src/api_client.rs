@@ -1,7 +1,8 @@
 use crate::model::TestRunReport;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::warn;
 
 /// Response returned after submitting a test run report.
@@ -17,8 +18,13 @@ pub struct TachyonOpsClient {
     api_key: String,
     operator_id: Option<String>,
     http: reqwest::Client,
+    /// SplitMix64 state used to jitter retry delays.
+    jitter_state: AtomicU64,
 }
 
+/// Base delay of the first retry, doubled on each attempt.
+const BASE_RETRY_DELAY_MS: u64 = 500;
+
 impl TachyonOpsClient {
     pub fn new(api_url: String, api_key: String) -> Self {
         let http = reqwest::Client::builder()
@@ -30,9 +36,35 @@ impl TachyonOpsClient {
             api_key,
             operator_id: None,
             http,
+            jitter_state: AtomicU64::new(time_seed()),
         }
     }
 
+    /// Seed the retry jitter RNG, making backoff delays
+    /// reproducible (used by tests).
+    pub fn with_jitter_seed(self, seed: u64) -> Self {
+        self.jitter_state.store(seed, Ordering::Relaxed);
+        self
+    }
+
+    /// Delay before retry `attempt` (0-based): full jitter, i.e.
+    /// uniformly random between 0 and `500 * 2^attempt` ms.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let cap = BASE_RETRY_DELAY_MS * 2u64.pow(attempt);
+        Duration::from_millis(self.next_random() % (cap + 1))
+    }
+
+    /// Next value of the SplitMix64 sequence.
+    fn next_random(&self) -> u64 {
+        let mut z = self
+            .jitter_state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
     /// Set the operator ID for multi-tenancy support.
     pub fn with_operator_id(mut self, operator_id: String) -> Self {
         self.operator_id = Some(operator_id);
@@ -41,8 +73,8 @@ impl TachyonOpsClient {
 
     /// Submit a test run report to the Tachyon Ops API.
     ///
-    /// Retries up to 3 times with exponential backoff on
-    /// transient failures (5xx, network errors).
+    /// Retries up to 3 times with exponential backoff and full
+    /// jitter on transient failures (5xx, network errors).
     pub async fn submit_report(
         &self,
         report: &TestRunReport,
@@ -77,8 +109,7 @@ impl TachyonOpsClient {
                 Ok(resp) if resp.status().is_server_error() => {
                     let status = resp.status();
                     if attempt < max_retries {
-                        let delay =
-                            Duration::from_millis(500 * 2u64.pow(attempt));
+                        let delay = self.retry_delay(attempt);
                         warn!(
                             status = %status,
                             attempt = attempt + 1,
@@ -98,8 +129,7 @@ impl TachyonOpsClient {
                 }
                 Err(e) => {
                     if attempt < max_retries {
-                        let delay =
-                            Duration::from_millis(500 * 2u64.pow(attempt));
+                        let delay = self.retry_delay(attempt);
                         warn!(
                             error = %e,
                             attempt = attempt + 1,
@@ -119,3 +149,48 @@ impl TachyonOpsClient {
         unreachable!()
     }
 }
+
+/// Seed derived from the current time, so concurrent CI jobs
+/// do not retry in lockstep.
+fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(seed: u64) -> TachyonOpsClient {
+        TachyonOpsClient::new(
+            "http://localhost".to_string(),
+            "key".to_string(),
+        )
+        .with_jitter_seed(seed)
+    }
+
+    #[test]
+    fn test_retry_delay_is_bounded_by_backoff_cap() {
+        let client = client(42);
+        for attempt in 0..4 {
+            for _ in 0..100 {
+                let cap = BASE_RETRY_DELAY_MS * 2u64.pow(attempt);
+                assert!(
+                    client.retry_delay(attempt).as_millis() as u64 <= cap
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_is_deterministic_under_seed() {
+        let delays = |seed| {
+            let client = client(seed);
+            (0..4).map(|i| client.retry_delay(i)).collect::<Vec<_>>()
+        };
+        assert_eq!(delays(7), delays(7));
+        assert_ne!(delays(7), delays(8));
+    }
+}
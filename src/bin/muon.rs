@@ -2,40 +2,142 @@
 
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
-use clap::{Parser, ValueEnum};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use muon::{
-    api_client::TachyonOpsClient, CiMetadata, DefaultTestRunner,
-    TestConfigManager, TestResult, TestRunReport, TestRunner, TestScenario,
+    api_client::TachyonOpsClient, detect_ci_metadata, execute_scenarios,
+    expand_glob, is_glob_pattern, is_scenario_file, latency_stats,
+    load_vars_file, parse_var, redact, reporting, soak, AssertionResult,
+    ClientConfig, DefaultTestRunner, LatencyStats, RequestInfo, RunOptions,
+    SkippedFile, StepObserver, StepResult, TestConfigManager, TestResult,
+    TestRunReport, TestScenario,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use std::process::exit;
-use std::time::Instant;
-use tracing::{debug, error, info};
-use tracing_subscriber::{fmt, EnvFilter};
+use std::process::{exit, Command};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, trace, warn};
+use tracing_subscriber::{fmt, fmt::writer::BoxMakeWriter, EnvFilter};
 
 /// Tachyon Scenario Runner - YAML-based API test execution tool.
+///
+/// Runs scenarios when no subcommand is given, so `muon -p FILE`
+/// is equivalent to `muon run -p FILE`.
 #[derive(Parser, Debug)]
-#[command(name = "muon", version, about)]
+#[command(
+    name = "muon",
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    disable_version_flag = true
+)]
 struct Cli {
-    /// Test file or directory path.
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// Print version (long form only: `-V` is `--var`).
+    #[arg(long = "version", action = ArgAction::Version)]
+    version: Option<bool>,
+
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Run test scenarios (the default when no subcommand is given).
+    Run(Box<RunArgs>),
+    /// List the tags used by the discovered scenarios with counts,
+    /// without running anything.
+    ListTags(ListTagsArgs),
+}
+
+/// Options for `list-tags`.
+#[derive(Args, Debug)]
+struct ListTagsArgs {
+    /// Test file or directory path, or a glob pattern such as
+    /// `tests/**/*.scenario.md`.
     #[arg(short = 'p', long = "path")]
     test_path: Option<String>,
 
+    /// Directory to discover scenarios in when `--path` is not
+    /// given (repeatable; comma-separated in the env var).
+    /// Replaces the default discovery directories.
+    #[arg(
+        long = "scenario-dir",
+        value_name = "DIR",
+        env = "MUON_SCENARIO_DIRS",
+        value_delimiter = ','
+    )]
+    scenario_dirs: Vec<PathBuf>,
+
+    /// Only load scenario files directly inside the scanned
+    /// directories instead of descending into subdirectories.
+    #[arg(long = "no-recursive")]
+    no_recursive: bool,
+
+    /// Enable verbose logging.
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Print the tags as `TAG<TAB>COUNT` lines or as one JSON
+    /// object.
+    #[arg(long = "output", default_value = "text")]
+    output: OutputFormat,
+}
+
+/// Options for running scenarios.
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Test file or directory path, or a glob pattern such as
+    /// `tests/**/*.scenario.md`.
+    #[arg(short = 'p', long = "path")]
+    test_path: Option<String>,
+
+    /// Directory to discover scenarios in when `--path` is not
+    /// given (repeatable; comma-separated in the env var).
+    /// Replaces the default discovery directories.
+    #[arg(
+        long = "scenario-dir",
+        value_name = "DIR",
+        env = "MUON_SCENARIO_DIRS",
+        value_delimiter = ','
+    )]
+    scenario_dirs: Vec<PathBuf>,
+
+    /// Only load scenario files directly inside the scanned
+    /// directories instead of descending into subdirectories.
+    #[arg(long = "no-recursive")]
+    no_recursive: bool,
+
     /// Filter tests by name (partial match).
     #[arg(short = 'f', long = "filter")]
     test_filter: Option<String>,
 
-    /// Enable verbose logging.
-    #[arg(short = 'v', long = "verbose")]
-    verbose: bool,
+    /// Enable verbose logging. Repeat (`-vv`) for trace-level
+    /// output including headers and untruncated bodies.
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count)]
+    verbose: u8,
 
     /// Timeout in seconds per test step.
     #[arg(short = 't', long = "timeout")]
     timeout: Option<u64>,
 
-    /// Directory to save test report files.
+    /// Number of scenarios to run concurrently. Results are
+    /// printed and counted as each scenario completes.
+    #[arg(short = 'j', long = "jobs", default_value_t = 1)]
+    jobs: usize,
+
+    /// Stop the run at the first failing scenario: scenarios still
+    /// running are aborted and the remaining ones are skipped.
+    #[arg(long = "fail-fast")]
+    fail_fast: bool,
+
+    /// Directory to save test report files, or `-` to stream the
+    /// reports to stdout (logs then go to stderr).
     #[arg(short = 'r', long = "report-dir")]
     report_dir: Option<String>,
 
@@ -43,10 +145,53 @@ struct Cli {
     #[arg(long = "report-format", default_value = "json")]
     report_format: ReportFormat,
 
+    /// Write one report for the whole run, with per-scenario and
+    /// overall totals, instead of one file per scenario.
+    #[arg(long = "report-aggregate", requires = "report_dir")]
+    report_aggregate: bool,
+
+    /// Environment to run against: each scenario's
+    /// `environments.<NAME>` vars are merged over its `vars`.
+    #[arg(long = "env", value_name = "NAME", env = "MUON_ENV")]
+    environment: Option<String>,
+
+    /// JSON or YAML file of variables merged into every
+    /// scenario's `vars` (repeatable; later files win). Scenario
+    /// vars take precedence unless `--vars-file-override` is set.
+    #[arg(long = "vars-file", value_name = "PATH")]
+    vars_files: Vec<PathBuf>,
+
+    /// Let `--vars-file` values replace scenario vars of the same
+    /// name.
+    #[arg(long = "vars-file-override", requires = "vars_files")]
+    vars_file_override: bool,
+
+    /// Set a variable in every scenario, overriding its `vars`
+    /// (repeatable). VALUE is parsed as JSON when possible, so
+    /// `count=5` is a number and `name=alice` a string.
+    #[arg(
+        short = 'V',
+        long = "var",
+        value_name = "KEY=VALUE",
+        value_parser = parse_var
+    )]
+    vars: Vec<(String, serde_json::Value)>,
+
     /// Base URL override for all scenarios.
     #[arg(short = 'b', long = "base-url")]
     base_url: Option<String>,
 
+    /// Send every request through this proxy. Without it the
+    /// standard HTTP_PROXY / HTTPS_PROXY / NO_PROXY variables are
+    /// used.
+    #[arg(long = "proxy", value_name = "URL", env = "MUON_PROXY")]
+    proxy: Option<String>,
+
+    /// Path prefix (e.g. `/v2`) prepended to relative request URLs
+    /// in all scenarios. Absolute URLs are unaffected.
+    #[arg(long = "base-path")]
+    base_path: Option<String>,
+
     /// Tachyon Ops API URL for submitting test results.
     #[arg(long = "api-url", env = "TACHYON_OPS_API_URL")]
     api_url: Option<String>,
@@ -58,6 +203,147 @@ struct Cli {
     /// Operator ID for multi-tenancy (x-operator-id header).
     #[arg(long = "operator-id", env = "TACHYON_OPS_OPERATOR_ID")]
     operator_id: Option<String>,
+
+    /// Credentials file (TOML, or JSON with a `.json` extension)
+    /// providing `api_url`, `api_key`, and `operator_id`. Flags
+    /// and environment variables take precedence.
+    #[arg(long = "credentials", value_name = "FILE")]
+    credentials: Option<PathBuf>,
+
+    /// Only run scenario files changed relative to a git base
+    /// ref (default `origin/main`). Runs everything when not
+    /// inside a git repository.
+    #[arg(
+        long = "changed-only",
+        value_name = "BASE_REF",
+        num_args = 0..=1,
+        default_missing_value = "origin/main"
+    )]
+    changed_only: Option<String>,
+
+    /// Record a redacted snapshot of the scenario variables at the
+    /// first failing step, printed with the failure and saved in
+    /// reports.
+    #[arg(long = "dump-vars-on-failure")]
+    dump_vars_on_failure: bool,
+
+    /// Record a redacted snapshot of the scenario variables after
+    /// every step, printed with each step and saved in reports.
+    #[arg(long = "dump-vars")]
+    dump_vars: bool,
+
+    /// Print every expectation of each step (status, headers,
+    /// JSON paths, contains, test, ...) with ✓/✗ and the compared
+    /// values, not just the first failure.
+    #[arg(long = "explain")]
+    explain: bool,
+
+    /// Resolve variables and print every request (method, URL,
+    /// headers, body) without sending it; expectations are not
+    /// checked.
+    #[arg(long = "dry-run", conflicts_with = "soak")]
+    dry_run: bool,
+
+    /// Stream one JSON object per completed step to FILE (`-` for
+    /// stdout) while the run progresses.
+    #[arg(long = "events-ndjson", value_name = "FILE")]
+    events_ndjson: Option<PathBuf>,
+
+    /// Log each scenario's effective config (after CLI overrides,
+    /// with sensitive headers redacted) before running.
+    #[arg(long = "print-config")]
+    print_config: bool,
+
+    /// Output format for the summary printed to stdout. With
+    /// `json`, logs are written to stderr so stdout holds only the
+    /// JSON document.
+    #[arg(long = "output", default_value = "text")]
+    output: OutputFormat,
+
+    /// Print the N slowest steps across all scenarios after the
+    /// run (default 10).
+    #[arg(
+        long = "profile",
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "10"
+    )]
+    profile: Option<usize>,
+
+    /// Report min/mean/p50/p95/p99/max step latency across the
+    /// whole run in the summary and submitted report.
+    #[arg(long = "latency-stats")]
+    latency_stats: bool,
+
+    /// After the initial run, re-run scenario files as they change
+    /// until Ctrl-C. Results are not submitted in watch mode.
+    #[arg(long = "watch")]
+    watch: bool,
+
+    /// Soak mode: instead of running each scenario once, run
+    /// randomly picked scenarios (weighted by their `weight`) for
+    /// DURATION, e.g. `30s`, `10m`, or `1h`. Honors `--jobs`.
+    #[arg(
+        long = "soak",
+        value_name = "DURATION",
+        value_parser = soak::parse_duration
+    )]
+    soak: Option<Duration>,
+
+    /// Seed for soak-mode scenario selection, for reproducible
+    /// runs. Defaults to a time-based seed that is logged.
+    #[arg(long = "seed", requires = "soak")]
+    seed: Option<u64>,
+}
+
+/// Tachyon Ops credentials loaded from `--credentials`.
+#[derive(Default, Deserialize)]
+struct Credentials {
+    api_url: Option<String>,
+    api_key: Option<String>,
+    operator_id: Option<String>,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("api_url", &self.api_url)
+            .field(
+                "api_key",
+                &self.api_key.as_ref().map(|_| redact::REDACTED),
+            )
+            .field("operator_id", &self.operator_id)
+            .finish()
+    }
+}
+
+impl Credentials {
+    /// Read credentials from a TOML file, or a JSON file when the
+    /// path ends in `.json`.
+    fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).context(format!(
+            "Failed to read credentials file: {}",
+            path.display()
+        ))?;
+        let credentials = if path.extension().is_some_and(|e| e == "json") {
+            serde_json::from_str(&content).context(format!(
+                "Failed to parse JSON credentials from {}",
+                path.display()
+            ))?
+        } else {
+            toml::from_str(&content).context(format!(
+                "Failed to parse TOML credentials from {}",
+                path.display()
+            ))?
+        };
+        Ok(credentials)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
@@ -65,11 +351,166 @@ enum ReportFormat {
     Json,
     Yaml,
     Text,
+    /// JUnit XML (one test suite per scenario).
+    Junit,
+    /// TAP version 13 (one test point per step).
+    Tap,
+    /// Self-contained HTML page per scenario.
+    Html,
+}
+
+/// Writes a JSON Lines event for every completed step.
+struct NdjsonObserver {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl NdjsonObserver {
+    /// Open `path` for writing, or stdout when `path` is `-`.
+    fn create(path: &Path) -> Result<Self> {
+        let out: Box<dyn Write + Send> = if path == Path::new("-") {
+            Box::new(std::io::stdout())
+        } else {
+            Box::new(File::create(path).context(format!(
+                "Failed to create events file: {}",
+                path.display()
+            ))?)
+        };
+        Ok(Self {
+            out: Mutex::new(out),
+        })
+    }
+}
+
+impl StepObserver for NdjsonObserver {
+    fn on_step(&self, scenario: &TestScenario, step: &StepResult) {
+        let event = serde_json::json!({
+            "scenario": scenario.name,
+            "step": step.name,
+            "success": step.success,
+            "skipped": step.skipped,
+            "skip_reason": step.skip_reason,
+            "duration_ms": step.duration_ms,
+            "error": step.error,
+        });
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(out, "{event}").and_then(|()| out.flush())
+        {
+            warn!("Failed to write step event: {}", e);
+        }
+    }
+}
+
+/// Process exit code used when a run is interrupted with Ctrl-C
+/// (128 + SIGINT, matching shell convention).
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// Outcome of [`run_all_tests`].
+struct RunOutcome {
+    success: bool,
+    results: Vec<TestResult>,
+    /// `true` when the run was cut short by Ctrl-C.
+    interrupted: bool,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    /// Scenarios aborted or never started because of `--fail-fast`.
+    not_run: usize,
+    duration_ms: u64,
+    /// Pass/fail counts keyed by scenario tag.
+    by_tag: BTreeMap<String, TagStats>,
+}
+
+/// Pass/fail counts for the scenarios carrying one tag.
+#[derive(Debug, Default, Serialize)]
+struct TagStats {
+    passed: usize,
+    failed: usize,
+}
+
+/// Record a scenario outcome under each of its tags.
+fn record_tag_outcome(
+    by_tag: &mut BTreeMap<String, TagStats>,
+    tags: &[String],
+    success: bool,
+) {
+    for tag in tags {
+        let stats = by_tag.entry(tag.clone()).or_default();
+        if success {
+            stats.passed += 1;
+        } else {
+            stats.failed += 1;
+        }
+    }
 }
 
-fn init_tracing(verbose: bool) {
+/// A step timing entry for `--profile`.
+#[derive(Debug, Serialize)]
+struct SlowStep {
+    scenario: String,
+    step: String,
+    duration_ms: u64,
+}
+
+/// Return the `n` slowest steps across `results`, slowest first.
+fn slowest_steps(results: &[TestResult], n: usize) -> Vec<SlowStep> {
+    let mut steps: Vec<SlowStep> =
+        results
+            .iter()
+            .flat_map(|result| {
+                result.steps.iter().filter(|step| !step.skipped).map(
+                    |step| SlowStep {
+                        scenario: result.name.clone(),
+                        step: step.name.clone(),
+                        duration_ms: step.duration_ms,
+                    },
+                )
+            })
+            .collect();
+    steps.sort_by_key(|s| std::cmp::Reverse(s.duration_ms));
+    steps.truncate(n);
+    steps
+}
+
+/// Print the run summary as JSON to stdout.
+fn print_json_summary(
+    outcome: &RunOutcome,
+    slowest: Option<&[SlowStep]>,
+    latency: Option<&LatencyStats>,
+) -> Result<()> {
+    let mut summary = serde_json::json!({
+        "success": outcome.success,
+        "total": outcome.passed + outcome.failed,
+        "passed": outcome.passed,
+        "failed": outcome.failed,
+        "skipped": outcome.skipped,
+        "not_run": outcome.not_run,
+        "skipped_steps": outcome
+            .results
+            .iter()
+            .flat_map(|r| &r.steps)
+            .filter(|s| s.skipped)
+            .count(),
+        "interrupted": outcome.interrupted,
+        "duration_ms": outcome.duration_ms,
+        "by_tag": outcome.by_tag,
+    });
+    if let Some(slowest) = slowest {
+        summary["slowest"] = serde_json::to_value(slowest)?;
+    }
+    if let Some(latency) = latency {
+        summary["latency"] = serde_json::to_value(latency)?;
+    }
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+    Ok(())
+}
+
+fn init_tracing(verbose: u8, stdout_reserved: bool) {
     if std::env::var_os("RUST_LOG").is_none() {
-        let level = if verbose { "debug" } else { "info" };
+        let level = match verbose {
+            0 => "info",
+            1 => "debug",
+            _ => "debug,muon=trace",
+        };
         std::env::set_var("RUST_LOG", level);
     }
 
@@ -77,8 +518,17 @@ fn init_tracing(verbose: bool) {
         return;
     }
 
+    // Keep stdout clean for a JSON summary, streamed reports or
+    // streamed step events
+    let writer = if stdout_reserved {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+
     let _ = fmt()
         .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(writer)
         .with_target(false)
         .with_level(true)
         .with_thread_ids(false)
@@ -86,7 +536,71 @@ fn init_tracing(verbose: bool) {
         .try_init();
 }
 
-fn print_test_result(result: &TestResult, verbose: bool) {
+/// Truncate a body for debug output, respecting UTF-8
+/// character boundaries.
+fn truncate_body(body: &str, max: usize) -> String {
+    if body.len() <= max {
+        return body.to_string();
+    }
+    let mut end = max;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...(truncated)", &body[..end])
+}
+
+/// `PASS`, `FAIL`, or `SKIP` for a step in text reports.
+fn step_status_label(step: &StepResult) -> &'static str {
+    if step.skipped {
+        "SKIP"
+    } else if step.success {
+        "PASS"
+    } else {
+        "FAIL"
+    }
+}
+
+/// Print the request a `--dry-run` step would have sent.
+fn print_dry_run_request(request: &RequestInfo) {
+    info!("     {} {}", request.method, request.url);
+    let query: BTreeMap<_, _> = request.query.iter().collect();
+    for (name, value) in query {
+        info!("     ?{}={}", name, value);
+    }
+    let headers: BTreeMap<_, _> = redact::redact_headers(&request.headers)
+        .into_iter()
+        .collect();
+    for (name, value) in headers {
+        info!("     {}: {}", name, value);
+    }
+    if let Some(body) = &request.body {
+        info!("     {}", redact::redact_body(body));
+    }
+}
+
+/// One `--explain` line: ✓/✗, the check, and compared values.
+fn explain_line(assertion: &AssertionResult) -> String {
+    let mark = if assertion.passed {
+        "\x1b[32m✓\x1b[0m"
+    } else {
+        "\x1b[31m✗\x1b[0m"
+    };
+    let mut line = format!("{} {}", mark, assertion.check);
+    match (&assertion.expected, &assertion.actual) {
+        (Some(expected), Some(actual)) => line
+            .push_str(&format!(" (expected {expected}, actual {actual})")),
+        (Some(expected), None) => {
+            line.push_str(&format!(" (expected {expected})"))
+        }
+        _ => {}
+    }
+    if let Some(error) = &assertion.error {
+        line.push_str(&format!(": {error}"));
+    }
+    line
+}
+
+fn print_test_result(result: &TestResult, verbose: u8) {
     let status = if result.success {
         "\x1b[32mPASS\x1b[0m"
     } else {
@@ -98,7 +612,9 @@ fn print_test_result(result: &TestResult, verbose: bool) {
     );
 
     for (i, step) in result.steps.iter().enumerate() {
-        let step_status = if step.success {
+        let step_status = if step.skipped {
+            "\x1b[33m-\x1b[0m"
+        } else if step.success {
             "\x1b[32m✓\x1b[0m"
         } else {
             "\x1b[31m✗\x1b[0m"
@@ -111,96 +627,284 @@ fn print_test_result(result: &TestResult, verbose: bool) {
             step.duration_ms
         );
 
+        if let Some(reason) = &step.skip_reason {
+            info!("     \x1b[33mSkipped: {}\x1b[0m", reason);
+        }
+        if step.skipped && step.skip_reason.as_deref() == Some("dry run") {
+            print_dry_run_request(&step.request);
+        }
+        for assertion in &step.assertions {
+            info!("     {}", explain_line(assertion));
+        }
         if let Some(error) = &step.error {
             error!("     \x1b[31mError: {}\x1b[0m", error);
         }
+        if !step.diff.is_empty() {
+            let color = std::io::stdout().is_terminal();
+            let mut lines = String::from("     Diff (expected vs actual):");
+            for line in reporting::format_diff(&step.diff, color).lines() {
+                lines.push_str(&format!("\n       {line}"));
+            }
+            error!("{}", lines);
+        }
+        if let Some(snapshot) = &step.vars_snapshot {
+            let sorted: BTreeMap<_, _> = snapshot.iter().collect();
+            let json =
+                serde_json::to_string_pretty(&sorted).unwrap_or_default();
+            if step.success {
+                info!("     Vars: {}", json);
+            } else {
+                error!("     Vars at failure: {}", json);
+            }
+        }
 
-        if verbose {
+        if verbose >= 2 {
+            trace!(
+                "     Request: {} {}",
+                step.request.method,
+                step.request.url
+            );
+            trace!(
+                "     Request headers: {:?}",
+                redact::redact_headers(&step.request.headers)
+            );
+            if let Some(body) = &step.request.body {
+                trace!("     Request body: {}", redact::redact_body(body));
+            }
+
+            if let Some(response) = &step.response {
+                trace!("     Response: Status {}", response.status);
+                trace!(
+                    "     Response headers: {:?}",
+                    redact::redact_headers(&response.headers)
+                );
+                if let Some(body) = &response.body {
+                    trace!(
+                        "     Response body: {}",
+                        redact::redact_body(body)
+                    );
+                }
+            }
+        } else if verbose == 1 {
             debug!(
                 "     Request: {} {}",
                 step.request.method, step.request.url
             );
             if let Some(body) = &step.request.body {
-                let truncated = if body.len() > 500 {
-                    format!("{}...(truncated)", &body[..500])
-                } else {
-                    body.clone()
-                };
-                debug!("     Request body: {}", truncated);
+                debug!(
+                    "     Request body: {}",
+                    truncate_body(&redact::redact_body(body), 500)
+                );
             }
 
             if let Some(response) = &step.response {
                 debug!("     Response: Status {}", response.status);
                 if let Some(body) = &response.body {
-                    let truncated = if body.len() > 500 {
-                        format!("{}...(truncated)", &body[..500])
-                    } else {
-                        body.clone()
-                    };
-                    debug!("     Response body: {}", truncated);
+                    debug!(
+                        "     Response body: {}",
+                        truncate_body(&redact::redact_body(body), 500)
+                    );
                 }
             }
         }
     }
 }
 
-fn save_test_report(
+/// Render `result` in `format`, returning the file extension and
+/// the report text.
+fn render_report(
     result: &TestResult,
-    report_dir: &Path,
     format: ReportFormat,
-) -> Result<PathBuf> {
-    if !report_dir.exists() {
-        fs::create_dir_all(report_dir)?;
-    }
+) -> Result<(&'static str, String)> {
+    let (extension, content) = match format {
+        ReportFormat::Json => {
+            ("json", serde_json::to_string_pretty(result)?)
+        }
+        ReportFormat::Yaml => ("yaml", serde_yaml::to_string(result)?),
+        ReportFormat::Junit => ("xml", reporting::junit_xml(result)),
+        ReportFormat::Tap => ("tap", reporting::tap(result)),
+        ReportFormat::Html => ("html", reporting::html(result)),
+        ReportFormat::Text => ("txt", text_report(result)),
+    };
 
-    let timestamp = Utc::now().timestamp();
-    let sanitized_name = result.name.replace([' ', '/'], "_");
+    Ok((extension, content))
+}
 
-    let (filename, content) = match format {
-        ReportFormat::Json => {
-            let filename = format!("{sanitized_name}-{timestamp}.json");
-            let content = serde_json::to_string_pretty(result)?;
-            (filename, content)
+/// Plain-text report for one scenario.
+fn text_report(result: &TestResult) -> String {
+    let mut content = String::new();
+    content.push_str(&format!("Test result: {}\n", result.name));
+    content.push_str(&format!(
+        "Status: {}\n",
+        if result.success { "PASS" } else { "FAIL" }
+    ));
+    if let Some(error) = &result.error {
+        content.push_str(&format!("Error: {error}\n"));
+    }
+    content.push_str(&format!("Duration: {} ms\n\n", result.duration_ms));
+    content.push_str("Steps:\n");
+    for (i, step) in result.steps.iter().enumerate() {
+        content.push_str(&format!(
+            "  {}. {} ({})\n",
+            i + 1,
+            step.name,
+            step_status_label(step)
+        ));
+        if let Some(reason) = &step.skip_reason {
+            content.push_str(&format!("     Skipped: {reason}\n"));
         }
-        ReportFormat::Yaml => {
-            let filename = format!("{sanitized_name}-{timestamp}.yaml");
-            let content = serde_yaml::to_string(result)?;
-            (filename, content)
+        if let Some(error) = &step.error {
+            content.push_str(&format!("     Error: {error}\n"));
         }
+        content
+            .push_str(&format!("     Duration: {} ms\n", step.duration_ms));
+    }
+    content
+}
+
+/// Render a whole run in `format` for `--report-aggregate`,
+/// returning the file extension and the report text.
+fn render_run_report(
+    report: &TestRunReport,
+    format: ReportFormat,
+) -> Result<(&'static str, String)> {
+    let (extension, content) = match format {
+        ReportFormat::Json => {
+            ("json", serde_json::to_string_pretty(report)?)
+        }
+        ReportFormat::Yaml => ("yaml", serde_yaml::to_string(report)?),
+        ReportFormat::Junit => ("xml", reporting::junit_xml_run(report)),
+        ReportFormat::Tap => ("tap", reporting::tap_run(report)),
+        ReportFormat::Html => ("html", reporting::html_run(report)),
         ReportFormat::Text => {
-            let filename = format!("{sanitized_name}-{timestamp}.txt");
-            let mut content = String::new();
-            content.push_str(&format!("Test result: {}\n", result.name));
-            content.push_str(&format!(
-                "Status: {}\n",
-                if result.success { "PASS" } else { "FAIL" }
-            ));
-            if let Some(error) = &result.error {
-                content.push_str(&format!("Error: {error}\n"));
+            let mut content = format!(
+                "Test run: {}\n{}\n",
+                report.timestamp,
+                reporting::RunTotals::of(report)
+                    .summary(report.total_duration_ms)
+            );
+            for result in &report.scenarios {
+                content.push('\n');
+                content.push_str(&text_report(result));
             }
-            content.push_str(&format!(
-                "Duration: {} ms\n\n",
-                result.duration_ms
-            ));
-            content.push_str("Steps:\n");
-            for (i, step) in result.steps.iter().enumerate() {
-                content.push_str(&format!(
-                    "  {}. {} ({})\n",
-                    i + 1,
-                    step.name,
-                    if step.success { "PASS" } else { "FAIL" }
-                ));
-                if let Some(error) = &step.error {
-                    content.push_str(&format!("     Error: {error}\n"));
+            ("txt", content)
+        }
+    };
+
+    Ok((extension, content))
+}
+
+/// `--report-dir` value that streams reports to stdout.
+const REPORT_STDOUT: &str = "-";
+
+/// Where finished scenario reports go during a run.
+enum ReportSink<'a> {
+    /// One file per scenario in this directory.
+    Dir(&'a Path),
+    /// Printed to stdout as each scenario completes. TAP output is
+    /// one document: the test points are numbered across
+    /// scenarios and the plan is printed last.
+    Stdout { tap_points: usize },
+    /// One report for the whole run, written when the run
+    /// finishes to this directory, or to stdout when `None`.
+    Aggregate(Option<&'a Path>),
+}
+
+impl<'a> ReportSink<'a> {
+    fn new(report_dir: Option<&'a Path>, aggregate: bool) -> Option<Self> {
+        let dir = report_dir?;
+        let stdout = dir == Path::new(REPORT_STDOUT);
+        Some(match (aggregate, stdout) {
+            (true, true) => ReportSink::Aggregate(None),
+            (true, false) => ReportSink::Aggregate(Some(dir)),
+            (false, true) => ReportSink::Stdout { tap_points: 0 },
+            (false, false) => ReportSink::Dir(dir),
+        })
+    }
+
+    fn begin(&self, format: ReportFormat) {
+        if let (ReportSink::Stdout { .. }, ReportFormat::Tap) =
+            (self, format)
+        {
+            println!("TAP version 13");
+        }
+    }
+
+    fn write(
+        &mut self,
+        result: &TestResult,
+        format: ReportFormat,
+    ) -> Result<()> {
+        match self {
+            ReportSink::Dir(dir) => {
+                let path = save_test_report(result, dir, format)?;
+                info!("Report saved: {}", path.display());
+            }
+            ReportSink::Stdout { tap_points } => {
+                if format == ReportFormat::Tap {
+                    let (tests, count) =
+                        reporting::tap_tests(result, *tap_points + 1);
+                    *tap_points += count;
+                    print!("{tests}");
+                } else {
+                    println!("{}", render_report(result, format)?.1);
                 }
-                content.push_str(&format!(
-                    "     Duration: {} ms\n",
-                    step.duration_ms
-                ));
+                std::io::stdout().flush()?;
             }
-            (filename, content)
+            ReportSink::Aggregate(_) => {}
         }
-    };
+        Ok(())
+    }
+
+    fn finish(
+        &self,
+        format: ReportFormat,
+        report: impl FnOnce() -> TestRunReport,
+    ) -> Result<()> {
+        match *self {
+            ReportSink::Stdout { tap_points } => {
+                if format == ReportFormat::Tap {
+                    println!("1..{tap_points}");
+                }
+            }
+            ReportSink::Aggregate(dir) => {
+                let (extension, content) =
+                    render_run_report(&report(), format)?;
+                match dir {
+                    Some(dir) => {
+                        fs::create_dir_all(dir)?;
+                        let timestamp = Utc::now().timestamp();
+                        let path = dir.join(format!(
+                            "muon-report-{timestamp}.{extension}"
+                        ));
+                        fs::write(&path, content)?;
+                        info!("Report saved: {}", path.display());
+                    }
+                    None => {
+                        print!("{content}");
+                        std::io::stdout().flush()?;
+                    }
+                }
+            }
+            ReportSink::Dir(_) => {}
+        }
+        Ok(())
+    }
+}
+
+fn save_test_report(
+    result: &TestResult,
+    report_dir: &Path,
+    format: ReportFormat,
+) -> Result<PathBuf> {
+    if !report_dir.exists() {
+        fs::create_dir_all(report_dir)?;
+    }
+
+    let timestamp = Utc::now().timestamp();
+    let sanitized_name = result.name.replace([' ', '/'], "_");
+    let (extension, content) = render_report(result, format)?;
+    let filename = format!("{sanitized_name}-{timestamp}.{extension}");
 
     let file_path = report_dir.join(filename);
     let mut file = File::create(&file_path)?;
@@ -211,43 +915,66 @@ fn save_test_report(
 
 fn prepare_config(
     test_path: Option<String>,
+    scenario_dirs: &[PathBuf],
+    changed_only: Option<&str>,
+    recursive: bool,
 ) -> Result<(TestConfigManager, Vec<TestScenario>)> {
     let mut config = TestConfigManager::new();
+    config.set_recursive(recursive);
 
-    let default_paths =
-        ["tests/scenarios", "testcase/scenarios", "test/scenarios"];
+    if scenario_dirs.is_empty() {
+        let default_paths =
+            ["tests/scenarios", "testcase/scenarios", "test/scenarios"];
 
-    for path in &default_paths {
-        if Path::new(path).exists() {
-            config.add_path(path);
+        for path in &default_paths {
+            if Path::new(path).exists() {
+                config.add_path(path);
+            }
+        }
+        config.add_path(".");
+    } else {
+        config.test_paths.clear();
+        for dir in scenario_dirs {
+            if !dir.is_dir() {
+                warn!("Scenario directory not found: {}", dir.display());
+            }
+            config.add_path(dir);
         }
     }
-    config.add_path(".");
-
-    let mut scenarios = Vec::new();
 
-    if let Some(path) = test_path {
-        let path = PathBuf::from(path);
-        if !path.exists() {
+    let test_path = test_path.map(PathBuf::from);
+    if let Some(path) = &test_path {
+        if !path.exists() && !is_glob_pattern(path) {
             return Err(anyhow!("Path does not exist: {}", path.display()));
         }
+    }
 
-        if path.is_file() {
-            scenarios.push(config.load_scenario(&path).context(
-                format!("Failed to load scenario: {}", path.display()),
-            )?);
-        } else if path.is_dir() {
-            let dir_scenarios =
-                config.load_scenarios_from_dir(&path).context(format!(
-                    "Failed to load scenarios from directory: {}",
-                    path.display()
-                ))?;
-            scenarios.extend(dir_scenarios);
+    if let Some(base_ref) = changed_only {
+        if let Some(changed) = git_changed_files(base_ref)? {
+            let files =
+                discover_scenario_files(&config, test_path.as_deref())?
+                    .into_iter()
+                    .filter(|file| {
+                        file.canonicalize()
+                            .is_ok_and(|p| changed.contains(&p))
+                    })
+                    .collect::<Vec<_>>();
+            info!(
+                "{} scenario file(s) changed relative to {}",
+                files.len(),
+                base_ref
+            );
+            let scenarios = config.load_scenario_files(&files);
+            return Ok((config, scenarios));
         }
-    } else {
-        scenarios = config.load_all_scenarios()?;
+        warn!("Not inside a git repository; running all scenarios");
     }
 
+    let scenarios = match test_path {
+        Some(path) => config.load_paths(&[path])?,
+        None => config.load_all_scenarios()?,
+    };
+
     if scenarios.is_empty() {
         return Err(anyhow!("No test scenarios found"));
     }
@@ -255,159 +982,672 @@ fn prepare_config(
     Ok((config, scenarios))
 }
 
-/// Detect CI metadata from environment variables.
-fn detect_ci_metadata() -> Option<CiMetadata> {
-    // GitHub Actions
-    if std::env::var("GITHUB_ACTIONS").is_ok() {
-        let repository =
-            std::env::var("GITHUB_REPOSITORY").unwrap_or_default();
-        let branch = std::env::var("GITHUB_REF_NAME")
-            .or_else(|_| std::env::var("GITHUB_HEAD_REF"))
-            .unwrap_or_default();
-        let commit_sha = std::env::var("GITHUB_SHA").unwrap_or_default();
-        let pr_number = std::env::var("PR_NUMBER")
-            .or_else(|_| {
-                // Try to extract from GITHUB_REF (refs/pull/123/merge)
-                std::env::var("GITHUB_REF")
-                    .map(|r| r.split('/').nth(2).unwrap_or("").to_string())
-            })
-            .ok()
-            .and_then(|n| n.parse::<u64>().ok());
-        let run_id = std::env::var("GITHUB_RUN_ID").ok();
-        let run_url = run_id.as_ref().map(|id| {
-            format!("https://github.com/{repository}/actions/runs/{id}")
-        });
+/// List the scenario files that would be loaded for `test_path`
+/// (or the configured search paths when it is `None`).
+fn discover_scenario_files(
+    config: &TestConfigManager,
+    test_path: Option<&Path>,
+) -> Result<Vec<PathBuf>> {
+    match test_path {
+        Some(path) if path.is_file() => Ok(vec![path.to_path_buf()]),
+        Some(path) if !path.exists() && is_glob_pattern(path) => {
+            expand_glob(path)
+        }
+        Some(path) => config.scenario_files_in_dir(path),
+        None => {
+            let mut files = Vec::new();
+            let mut seen = HashSet::new();
+            for dir in &config.test_paths {
+                if dir.is_dir() {
+                    for file in config.scenario_files_in_dir(dir)? {
+                        let key = file
+                            .canonicalize()
+                            .unwrap_or_else(|_| file.clone());
+                        if seen.insert(key) {
+                            files.push(file);
+                        }
+                    }
+                }
+            }
+            Ok(files)
+        }
+    }
+}
 
-        return Some(CiMetadata {
-            provider: "github".to_string(),
-            repository,
-            branch,
-            commit_sha,
-            pr_number,
-            run_id,
-            run_url,
-        });
+/// Return the canonical paths of files changed relative to
+/// `base_ref`, or `None` when not inside a git repository.
+fn git_changed_files(base_ref: &str) -> Result<Option<HashSet<PathBuf>>> {
+    let toplevel = match Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+    {
+        Ok(output) if output.status.success() => PathBuf::from(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => return Ok(None),
+    };
+
+    let output = Command::new("git")
+        .args(["diff", "--name-only", base_ref])
+        .current_dir(&toplevel)
+        .output()
+        .context("Failed to run git diff")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git diff against '{}' failed: {}",
+            base_ref,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
 
-    None
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| toplevel.join(line).canonicalize().ok())
+            .collect(),
+    ))
 }
 
+/// Count how many scenarios carry each tag.
+fn collect_tag_counts(
+    scenarios: &[TestScenario],
+) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for scenario in scenarios {
+        for tag in &scenario.tags {
+            *counts.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Print the unique tags of `scenarios` with their counts.
+fn print_tag_list(
+    scenarios: &[TestScenario],
+    output: OutputFormat,
+) -> Result<()> {
+    let counts = collect_tag_counts(scenarios);
+    match output {
+        OutputFormat::Text => {
+            for (tag, count) in &counts {
+                println!("{tag}\t{count}");
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&counts)?);
+        }
+    }
+    Ok(())
+}
+
+/// Log the effective config of `scenario` with sensitive headers
+/// redacted.
+fn print_effective_config(scenario: &TestScenario) -> Result<()> {
+    let mut config = scenario.config.clone();
+    config.headers = redact::redact_headers(&config.headers);
+    config.auth = config.auth.as_ref().map(|auth| auth.redacted());
+    info!(
+        "Effective config for '{}':\n{}",
+        scenario.name,
+        serde_json::to_string_pretty(&config)?
+    );
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn run_all_tests(
     scenarios: Vec<TestScenario>,
-    test_filter: Option<String>,
-    timeout_override: Option<u64>,
-    base_url_override: Option<String>,
-    verbose: bool,
+    skipped_files: &[SkippedFile],
+    options: RunOptions,
+    verbose: u8,
     report_dir: Option<&Path>,
     report_format: ReportFormat,
-) -> Result<(bool, Vec<TestResult>)> {
-    let runner = DefaultTestRunner::new();
-    let mut all_success = true;
+    report_aggregate: bool,
+    print_config: bool,
+) -> Result<RunOutcome> {
     let total_start = Instant::now();
     let mut passed = 0;
     let mut failed = 0;
     let mut results = Vec::new();
+    let mut by_tag = BTreeMap::new();
 
-    let filtered: Vec<TestScenario> = match &test_filter {
-        Some(filter) => scenarios
-            .into_iter()
-            .filter(|s| {
-                s.name.to_lowercase().contains(&filter.to_lowercase())
-            })
-            .collect(),
-        None => scenarios,
-    };
+    let filtered = options.prepare(scenarios)?;
 
     if filtered.is_empty() {
         return Err(anyhow!("No tests matching the filter were found"));
     }
 
-    let total_tests = filtered.len();
-    info!("Running {} test(s)...", total_tests);
-
-    for (idx, mut scenario) in filtered.into_iter().enumerate() {
-        if let Some(timeout) = timeout_override {
-            scenario.config.timeout = timeout;
-        }
-        if let Some(ref base_url) = base_url_override {
-            scenario.config.base_url = Some(base_url.clone());
+    if print_config {
+        for scenario in &filtered {
+            print_effective_config(scenario)?;
         }
+    }
 
-        info!("Test {}/{}: {}", idx + 1, total_tests, scenario.name);
-        match runner.run(&scenario).await {
-            Ok(result) => {
-                print_test_result(&result, verbose);
+    info!("Running {} test(s)...", filtered.len());
+    let mut report_sink = ReportSink::new(report_dir, report_aggregate);
+    if let Some(sink) = &report_sink {
+        sink.begin(report_format);
+    }
 
-                if let Some(dir) = report_dir {
-                    match save_test_report(&result, dir, report_format) {
-                        Ok(path) => {
-                            info!("Report saved: {}", path.display())
-                        }
-                        Err(e) => error!("Failed to save report: {}", e),
-                    }
-                }
+    let with_latency = options.latency_stats;
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    // Printing, report saving, and counting happen as each scenario
+    // completes, on this task
+    let summary = execute_scenarios(
+        filtered,
+        options,
+        ctrl_c,
+        |_, scenario, result| {
+            print_test_result(&result, verbose);
 
-                if result.success {
-                    passed += 1;
-                } else {
-                    all_success = false;
-                    failed += 1;
+            if let Some(sink) = report_sink.as_mut() {
+                if let Err(e) = sink.write(&result, report_format) {
+                    error!("Failed to save report: {}", e);
                 }
-                results.push(result);
             }
-            Err(e) => {
-                error!(
-                    "\x1b[31mTest execution error: {} - {}\x1b[0m",
-                    scenario.name, e
-                );
-                all_success = false;
+
+            record_tag_outcome(&mut by_tag, &scenario.tags, result.success);
+            if result.success {
+                passed += 1;
+            } else {
                 failed += 1;
             }
+            results.push(result);
+        },
+    )
+    .await;
+    let interrupted = summary.interrupted;
+    let not_run = summary.not_run;
+    let all_success = failed == 0 && !interrupted;
+
+    if let Some(sink) = &report_sink {
+        let report = || TestRunReport {
+            scenarios: results.clone(),
+            total_duration_ms: total_start.elapsed().as_millis() as u64,
+            timestamp: Utc::now().to_rfc3339(),
+            ci: detect_ci_metadata(),
+            latency: with_latency
+                .then(|| latency_stats(&results))
+                .flatten(),
+        };
+        if let Err(e) = sink.finish(report_format, report) {
+            error!("Failed to save report: {}", e);
         }
     }
 
     let total_duration = total_start.elapsed().as_millis();
+    let mut skipped_summary =
+        format!("Skipped (load error): {}", skipped_files.len());
+    for skipped in skipped_files {
+        skipped_summary.push_str(&format!(
+            "\n    - {}: {}",
+            skipped.path.display(),
+            skipped.error
+        ));
+    }
+    let skipped_steps = results
+        .iter()
+        .flat_map(|r: &TestResult| &r.steps)
+        .filter(|s| s.skipped)
+        .count();
+    if skipped_steps > 0 {
+        skipped_summary
+            .push_str(&format!("\n  Skipped steps: {skipped_steps}"));
+    }
+    if not_run > 0 {
+        skipped_summary
+            .push_str(&format!("\n  Skipped (fail-fast): {not_run}"));
+    }
+    if interrupted {
+        skipped_summary.push_str("\n  Interrupted: yes");
+    }
+    if !by_tag.is_empty() {
+        skipped_summary.push_str("\n  By tag:");
+        for (tag, stats) in &by_tag {
+            skipped_summary.push_str(&format!(
+                "\n    - {}: {} passed, {} failed",
+                tag, stats.passed, stats.failed
+            ));
+        }
+    }
     info!(
-        "Summary:\n  Total: {}\n  \x1b[32mPassed: {}\x1b[0m\n  \x1b[31mFailed: {}\x1b[0m\n  Duration: {} ms",
+        "Summary:\n  Total: {}\n  \x1b[32mPassed: {}\x1b[0m\n  \x1b[31mFailed: {}\x1b[0m\n  {}\n  Duration: {} ms",
         passed + failed,
         passed,
         failed,
+        skipped_summary,
         total_duration
     );
 
-    Ok((all_success, results))
+    Ok(RunOutcome {
+        success: all_success,
+        results,
+        interrupted,
+        passed,
+        failed,
+        skipped: skipped_files.len(),
+        not_run,
+        duration_ms: total_duration as u64,
+        by_tag,
+    })
+}
+
+/// Build the options for one run from the CLI arguments.
+fn run_options(
+    args: &RunArgs,
+    observer: Option<&Arc<dyn StepObserver>>,
+) -> Result<RunOptions> {
+    let runner = DefaultTestRunner::with_client_config(ClientConfig {
+        proxy: args.proxy.clone(),
+        ..Default::default()
+    })?;
+    let mut runner = runner
+        .with_dump_vars_on_failure(args.dump_vars_on_failure)
+        .with_dump_vars(args.dump_vars)
+        .with_explain(args.explain)
+        .with_dry_run(args.dry_run);
+    if let Some(observer) = observer {
+        runner = runner.with_observer(Arc::clone(observer));
+    }
+    let mut file_vars = HashMap::new();
+    for path in &args.vars_files {
+        file_vars.extend(load_vars_file(path)?);
+    }
+    let (default_vars, mut override_vars) = if args.vars_file_override {
+        (HashMap::new(), file_vars)
+    } else {
+        (file_vars, HashMap::new())
+    };
+    override_vars.extend(args.vars.iter().cloned());
+    Ok(RunOptions {
+        filter: args.test_filter.clone(),
+        timeout: args.timeout,
+        base_url: args.base_url.clone(),
+        base_path: args.base_path.clone(),
+        concurrency: args.jobs,
+        fail_fast: args.fail_fast,
+        latency_stats: args.latency_stats,
+        environment: args.environment.clone(),
+        default_vars,
+        override_vars,
+        runner,
+    })
+}
+
+/// Run `--soak` mode and print the aggregate report. Exits with
+/// status 1 when any run failed.
+async fn run_soak_command(
+    args: &RunArgs,
+    scenarios: Vec<TestScenario>,
+    duration: Duration,
+) -> Result<()> {
+    if scenarios.is_empty() {
+        anyhow::bail!("No scenarios to soak");
+    }
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+
+    let report =
+        soak::run_soak(scenarios, run_options(args, None)?, duration, seed)
+            .await?;
+
+    match args.output {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Text => {
+            let mut lines = format!(
+                "Soak summary (seed {}):\n  Iterations: {}\n  \
+                 \x1b[32mPassed: {}\x1b[0m\n  \
+                 \x1b[31mFailed: {}\x1b[0m\n  Duration: {} ms",
+                report.seed,
+                report.iterations,
+                report.passed,
+                report.failed,
+                report.duration_ms
+            );
+            for (name, stats) in &report.scenarios {
+                lines.push_str(&format!(
+                    "\n  {}: {} passed, {} failed",
+                    name, stats.passed, stats.failed
+                ));
+            }
+            if let Some(stats) = &report.latency {
+                lines.push_str(&format!(
+                    "\n  Step latency ({} steps): min {} ms, mean {} ms, \
+                     p50 {} ms, p95 {} ms, p99 {} ms, max {} ms",
+                    stats.count,
+                    stats.min_ms,
+                    stats.mean_ms,
+                    stats.p50_ms,
+                    stats.p95_ms,
+                    stats.p99_ms,
+                    stats.max_ms
+                ));
+            }
+            info!("{}", lines);
+        }
+    }
+
+    if report.failed > 0 {
+        exit(1);
+    }
+    Ok(())
+}
+
+/// How long the file system must stay quiet before a watch
+/// re-run starts, so editor save bursts trigger one run.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Directories to watch, plus the files to react to when
+/// `--path` names a file or a glob pattern.
+struct WatchTargets {
+    dirs: Vec<PathBuf>,
+    only: Option<HashSet<PathBuf>>,
+}
+
+fn watch_targets(
+    config: &TestConfigManager,
+    test_path: Option<&str>,
+) -> WatchTargets {
+    match test_path.map(Path::new) {
+        Some(path) if path.is_file() => WatchTargets {
+            dirs: vec![path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new("."))
+                .to_path_buf()],
+            only: path.canonicalize().ok().map(|p| HashSet::from([p])),
+        },
+        Some(path) if !path.exists() && is_glob_pattern(path) => {
+            let files = expand_glob(path).unwrap_or_default();
+            let mut dirs = Vec::new();
+            for file in &files {
+                let dir = file
+                    .parent()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .unwrap_or(Path::new("."));
+                if !dirs.iter().any(|d| d == dir) {
+                    dirs.push(dir.to_path_buf());
+                }
+            }
+            WatchTargets {
+                dirs,
+                only: Some(
+                    files
+                        .iter()
+                        .filter_map(|f| f.canonicalize().ok())
+                        .collect(),
+                ),
+            }
+        }
+        Some(path) => WatchTargets {
+            dirs: vec![path.to_path_buf()],
+            only: None,
+        },
+        None => WatchTargets {
+            dirs: config
+                .test_paths
+                .iter()
+                .filter(|dir| dir.is_dir())
+                .cloned()
+                .collect(),
+            only: None,
+        },
+    }
+}
+
+/// Watch the scenario directories and re-run changed scenario
+/// files, printing a fresh summary each time, until Ctrl-C.
+async fn watch_and_rerun(
+    args: &RunArgs,
+    targets: &WatchTargets,
+    observer: Option<&Arc<dyn StepObserver>>,
+    report_dir: Option<&Path>,
+) -> Result<()> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(
+        move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_)
+                ) {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+            }
+        },
+    )
+    .context("Failed to start file watcher")?;
+    let mode = if args.no_recursive || targets.only.is_some() {
+        RecursiveMode::NonRecursive
+    } else {
+        RecursiveMode::Recursive
+    };
+    for dir in &targets.dirs {
+        watcher
+            .watch(dir, mode)
+            .context(format!("Failed to watch {}", dir.display()))?;
+        info!("Watching {} for changes (Ctrl-C to stop)", dir.display());
+    }
+
+    loop {
+        let first = tokio::select! {
+            path = rx.recv() => match path {
+                Some(path) => path,
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        };
+        let mut changed = BTreeSet::from([first]);
+        while let Ok(Some(path)) =
+            tokio::time::timeout(WATCH_DEBOUNCE, rx.recv()).await
+        {
+            changed.insert(path);
+        }
+
+        let files: Vec<PathBuf> = changed
+            .into_iter()
+            .filter(|path| path.is_file() && is_scenario_file(path))
+            .filter(|path| {
+                targets.only.as_ref().is_none_or(|only| {
+                    path.canonicalize().is_ok_and(|p| only.contains(&p))
+                })
+            })
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        for file in &files {
+            info!("Change detected: {}", file.display());
+        }
+
+        let mut manager = TestConfigManager::new();
+        let scenarios = manager.load_scenario_files(&files);
+        for skipped in manager.skipped_files() {
+            error!(
+                "Failed to load {}: {}",
+                skipped.path.display(),
+                skipped.error
+            );
+        }
+        if scenarios.is_empty() {
+            continue;
+        }
+        if let Err(e) = run_all_tests(
+            scenarios,
+            manager.skipped_files(),
+            run_options(args, observer)?,
+            args.verbose,
+            report_dir,
+            args.report_format,
+            args.report_aggregate,
+            args.print_config,
+        )
+        .await
+        {
+            warn!("{}", e);
+        }
+    }
+
+    info!("Watch mode stopped");
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Cli::parse();
+    let cli = Cli::parse();
 
-    init_tracing(args.verbose);
+    match cli.command {
+        Some(Commands::Run(args)) => run_command(*args).await,
+        Some(Commands::ListTags(args)) => list_tags_command(args),
+        None => run_command(cli.run).await,
+    }
+}
 
-    let (_, scenarios) = prepare_config(args.test_path)?;
+/// Execute the `list-tags` command.
+fn list_tags_command(args: ListTagsArgs) -> Result<()> {
+    // The listing is the only thing written to stdout
+    init_tracing(args.verbose, true);
+    let (_, scenarios) = prepare_config(
+        args.test_path,
+        &args.scenario_dirs,
+        None,
+        !args.no_recursive,
+    )?;
+    print_tag_list(&scenarios, args.output)
+}
 
-    let report_dir = args.report_dir.map(PathBuf::from);
+/// Execute the `run` command (also the default command).
+async fn run_command(mut args: RunArgs) -> Result<()> {
+    init_tracing(
+        args.verbose,
+        args.output == OutputFormat::Json
+            || args.report_dir.as_deref() == Some(REPORT_STDOUT)
+            || args.events_ndjson.as_deref() == Some(Path::new("-")),
+    );
+
+    if let Some(ref path) = args.credentials {
+        let credentials = Credentials::load(path)?;
+        debug!(
+            "Loaded credentials from {}: {:?}",
+            path.display(),
+            credentials
+        );
+        args.api_url = args.api_url.or(credentials.api_url);
+        args.api_key = args.api_key.or(credentials.api_key);
+        args.operator_id = args.operator_id.or(credentials.operator_id);
+    }
+
+    let (config, scenarios) = prepare_config(
+        args.test_path.clone(),
+        &args.scenario_dirs,
+        args.changed_only.as_deref(),
+        !args.no_recursive,
+    )?;
+
+    if scenarios.is_empty() && args.changed_only.is_some() {
+        info!("No changed scenarios to run");
+        return Ok(());
+    }
+
+    if let Some(duration) = args.soak {
+        return run_soak_command(&args, scenarios, duration).await;
+    }
+
+    let report_dir = args.report_dir.clone().map(PathBuf::from);
 
     let total_start = Instant::now();
 
-    let (success, results) = run_all_tests(
+    let observer: Option<Arc<dyn StepObserver>> = match args.events_ndjson {
+        Some(ref path) => Some(Arc::new(NdjsonObserver::create(path)?)),
+        None => None,
+    };
+
+    let outcome = run_all_tests(
         scenarios,
-        args.test_filter,
-        args.timeout,
-        args.base_url,
+        config.skipped_files(),
+        run_options(&args, observer.as_ref())?,
         args.verbose,
         report_dir.as_deref(),
         args.report_format,
+        args.report_aggregate,
+        args.print_config,
     )
     .await?;
 
-    // Submit report to Tachyon Ops API if configured
+    let slowest = args.profile.map(|n| slowest_steps(&outcome.results, n));
+    let latency = if args.latency_stats {
+        latency_stats(&outcome.results)
+    } else {
+        None
+    };
+    match args.output {
+        OutputFormat::Json => print_json_summary(
+            &outcome,
+            slowest.as_deref(),
+            latency.as_ref(),
+        )?,
+        OutputFormat::Text => {
+            if let Some(stats) = &latency {
+                info!(
+                    "Step latency ({} steps): min {} ms, mean {} ms, \
+                     p50 {} ms, p95 {} ms, p99 {} ms, max {} ms",
+                    stats.count,
+                    stats.min_ms,
+                    stats.mean_ms,
+                    stats.p50_ms,
+                    stats.p95_ms,
+                    stats.p99_ms,
+                    stats.max_ms
+                );
+            }
+            if let Some(slowest) = &slowest {
+                let mut lines = String::from("Slowest steps:");
+                for (i, s) in slowest.iter().enumerate() {
+                    lines.push_str(&format!(
+                        "\n  {}. {} / {} ({} ms)",
+                        i + 1,
+                        s.scenario,
+                        s.step,
+                        s.duration_ms
+                    ));
+                }
+                info!("{}", lines);
+            }
+        }
+    }
+
+    if args.watch {
+        let targets = watch_targets(&config, args.test_path.as_deref());
+        return watch_and_rerun(
+            &args,
+            &targets,
+            observer.as_ref(),
+            report_dir.as_deref(),
+        )
+        .await;
+    }
+
+    // Submit report to Tachyon Ops API if configured (partial
+    // results are still submitted after an interruption)
     if let (Some(api_url), Some(api_key)) = (args.api_url, args.api_key) {
         let report = TestRunReport {
-            scenarios: results,
+            scenarios: outcome.results,
             total_duration_ms: total_start.elapsed().as_millis() as u64,
             timestamp: Utc::now().to_rfc3339(),
             ci: detect_ci_metadata(),
+            latency,
         };
 
         info!("Submitting test report to Tachyon Ops API...");
@@ -429,7 +1669,10 @@ async fn main() -> Result<()> {
         }
     }
 
-    if !success {
+    if outcome.interrupted {
+        exit(EXIT_INTERRUPTED);
+    }
+    if !outcome.success {
         exit(1);
     }
 
@@ -2,15 +2,30 @@
 
 use crate::model::TestScenario;
 use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
+/// A scenario file that was discovered but could not be loaded.
+#[derive(Debug, Clone)]
+pub struct SkippedFile {
+    /// Path of the file that failed to load.
+    pub path: PathBuf,
+    /// Human-readable load error.
+    pub error: String,
+}
+
 /// TODO: add English documentation
 #[derive(Debug)]
 pub struct TestConfigManager {
     /// TODO: add English documentation
     pub test_paths: Vec<PathBuf>,
+    /// Files skipped during lenient directory loading.
+    skipped: Vec<SkippedFile>,
+    /// Whether directory scans descend into subdirectories.
+    recursive: bool,
 }
 
 impl TestConfigManager {
@@ -18,9 +33,24 @@ impl TestConfigManager {
     pub fn new() -> Self {
         Self {
             test_paths: vec![PathBuf::from("tests/scenarios")],
+            skipped: Vec::new(),
+            recursive: true,
         }
     }
 
+    /// Choose whether directory scans descend into subdirectories
+    /// (the default) or only read the directory itself.
+    pub fn set_recursive(&mut self, recursive: bool) -> &mut Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Scenario files that were skipped because they failed to
+    /// load while scanning directories.
+    pub fn skipped_files(&self) -> &[SkippedFile] {
+        &self.skipped
+    }
+
     /// TODO: add English documentation
     pub fn add_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
         self.test_paths.push(path.as_ref().to_path_buf());
@@ -44,7 +74,7 @@ impl TestConfigManager {
             path.display()
         ))?;
 
-        let scenario = if is_markdown_scenario(path) {
+        let mut scenario = if is_markdown_scenario(path) {
             TestScenario::from_markdown(&content).context(format!(
                 "Failed to parse Markdown scenario from {}",
                 path.display()
@@ -61,19 +91,62 @@ impl TestConfigManager {
             ))?
         };
 
+        scenario.config.base_dir = path.parent().map(Path::to_path_buf);
         debug!("Successfully loaded test scenario: {}", scenario.name);
         Ok(scenario)
     }
 
-    /// TODO: add English documentation
-    pub fn load_scenarios_from_dir<P: AsRef<Path>>(
+    /// Load every scenario in a file.
+    ///
+    /// A `.yaml` / `.yml` scenario file may hold several scenarios
+    /// separated by `---` document markers; other files yield
+    /// exactly one scenario as with [`Self::load_scenario`].
+    pub fn load_scenarios_from_file<P: AsRef<Path>>(
         &self,
-        dir: P,
+        path: P,
     ) -> Result<Vec<TestScenario>> {
-        let dir = dir.as_ref();
-        info!("Loading test scenarios from directory: {}", dir.display());
+        let path = path.as_ref();
+        if is_markdown_scenario(path)
+            || crate::runn_parser::is_runbook_file(path)
+        {
+            return Ok(vec![self.load_scenario(path)?]);
+        }
+        info!("Loading test scenarios from {}", path.display());
+
+        let content = fs::read_to_string(path).context(format!(
+            "Failed to read test file: {}",
+            path.display()
+        ))?;
 
         let mut scenarios = Vec::new();
+        for (i, document) in
+            serde_yaml::Deserializer::from_str(&content).enumerate()
+        {
+            let mut scenario = TestScenario::deserialize(document)
+                .context(format!(
+                    "Failed to parse YAML document {} from {}",
+                    i + 1,
+                    path.display()
+                ))?;
+            scenario.config.base_dir = path.parent().map(Path::to_path_buf);
+            debug!("Successfully loaded test scenario: {}", scenario.name);
+            scenarios.push(scenario);
+        }
+        Ok(scenarios)
+    }
+
+    /// List the scenario files inside `dir`.
+    ///
+    /// Subdirectories are scanned too unless recursion was turned
+    /// off with [`Self::set_recursive`]; hidden directories and
+    /// `target` are never entered.
+    pub fn scenario_files_in_dir<P: AsRef<Path>>(
+        &self,
+        dir: P,
+    ) -> Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let mut files = Vec::new();
+        let mut subdirs = Vec::new();
 
         for entry in fs::read_dir(dir).context(format!(
             "Failed to read directory: {}",
@@ -81,21 +154,68 @@ impl TestConfigManager {
         ))? {
             let entry = entry?;
             let path = entry.path();
-
             if path.is_file() && is_scenario_file(&path) {
-                match self.load_scenario(&path) {
-                    Ok(scenario) => scenarios.push(scenario),
-                    Err(err) => {
-                        debug!(
-                            "Failed to load scenario from {}: {}",
-                            path.display(),
-                            err
-                        );
-                    }
+                files.push(path);
+            } else if self.recursive
+                && entry.file_type()?.is_dir()
+                && !is_skipped_dir(&path)
+            {
+                subdirs.push(path);
+            }
+        }
+
+        subdirs.sort();
+        for subdir in subdirs {
+            files.extend(self.scenario_files_in_dir(subdir)?);
+        }
+
+        Ok(files)
+    }
+
+    /// Load the given scenario files.
+    ///
+    /// Files that fail to load are skipped and recorded in
+    /// [`Self::skipped_files`].
+    pub fn load_scenario_files(
+        &mut self,
+        files: &[PathBuf],
+    ) -> Vec<TestScenario> {
+        let mut scenarios = Vec::new();
+
+        for path in files {
+            match self.load_scenarios_from_file(path) {
+                Ok(loaded) => scenarios.extend(loaded),
+                Err(err) => {
+                    debug!(
+                        "Failed to load scenario from {}: {}",
+                        path.display(),
+                        err
+                    );
+                    self.skipped.push(SkippedFile {
+                        path: path.clone(),
+                        error: format!("{err:#}"),
+                    });
                 }
             }
         }
 
+        scenarios
+    }
+
+    /// Load every scenario file in `dir`.
+    ///
+    /// Files that fail to load are skipped and recorded in
+    /// [`Self::skipped_files`].
+    pub fn load_scenarios_from_dir<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+    ) -> Result<Vec<TestScenario>> {
+        let dir = dir.as_ref();
+        info!("Loading test scenarios from directory: {}", dir.display());
+
+        let files = self.scenario_files_in_dir(dir)?;
+        let scenarios = self.load_scenario_files(&files);
+
         info!(
             "Loaded {} test scenarios from {}",
             scenarios.len(),
@@ -104,15 +224,70 @@ impl TestConfigManager {
         Ok(scenarios)
     }
 
-    /// TODO: add English documentation
-    pub fn load_all_scenarios(&self) -> Result<Vec<TestScenario>> {
-        let mut all_scenarios = Vec::new();
+    /// Load scenarios from explicit paths.
+    ///
+    /// A file path must load successfully; directories are loaded
+    /// leniently like [`Self::load_scenarios_from_dir`]. A path
+    /// containing glob metacharacters (see [`expand_glob`]) loads
+    /// every matching scenario file.
+    pub fn load_paths(
+        &mut self,
+        paths: &[PathBuf],
+    ) -> Result<Vec<TestScenario>> {
+        let mut scenarios = Vec::new();
+        for path in paths {
+            if !path.exists() && is_glob_pattern(path) {
+                for file in expand_glob(path)? {
+                    scenarios.extend(
+                        self.load_scenarios_from_file(&file).context(
+                            format!(
+                                "Failed to load scenario: {}",
+                                file.display()
+                            ),
+                        )?,
+                    );
+                }
+            } else if path.is_file() {
+                scenarios.extend(
+                    self.load_scenarios_from_file(path).context(
+                        format!(
+                            "Failed to load scenario: {}",
+                            path.display()
+                        ),
+                    )?,
+                );
+            } else if path.is_dir() {
+                scenarios.extend(
+                    self.load_scenarios_from_dir(path).context(format!(
+                        "Failed to load scenarios from directory: {}",
+                        path.display()
+                    ))?,
+                );
+            } else {
+                anyhow::bail!("Path does not exist: {}", path.display());
+            }
+        }
+        Ok(scenarios)
+    }
+
+    /// Load every scenario under the configured search paths.
+    ///
+    /// A file reachable from several search paths (e.g. `.` and
+    /// `tests/scenarios` when scanning recursively) is loaded once.
+    pub fn load_all_scenarios(&mut self) -> Result<Vec<TestScenario>> {
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
 
-        for path in &self.test_paths {
+        for path in self.test_paths.clone() {
             if path.exists() && path.is_dir() {
-                match self.load_scenarios_from_dir(path) {
-                    Ok(mut scenarios) => {
-                        all_scenarios.append(&mut scenarios)
+                match self.scenario_files_in_dir(&path) {
+                    Ok(found) => {
+                        files.extend(found.into_iter().filter(|file| {
+                            seen.insert(
+                                file.canonicalize()
+                                    .unwrap_or_else(|_| file.clone()),
+                            )
+                        }))
                     }
                     Err(err) => {
                         debug!(
@@ -125,6 +300,7 @@ impl TestConfigManager {
             }
         }
 
+        let all_scenarios = self.load_scenario_files(&files);
         info!("Loaded {} test scenarios in total", all_scenarios.len());
         Ok(all_scenarios)
     }
@@ -138,7 +314,7 @@ impl Default for TestConfigManager {
 
 /// Return `true` if the file path looks like a scenario file
 /// (`.yaml`, `.yml`, `.scenario.md`, or `.runbook.yml`).
-fn is_scenario_file(path: &Path) -> bool {
+pub fn is_scenario_file(path: &Path) -> bool {
     if is_markdown_scenario(path) {
         return true;
     }
@@ -149,6 +325,179 @@ fn is_scenario_file(path: &Path) -> bool {
         .is_some_and(|ext| ext == "yaml" || ext == "yml")
 }
 
+/// Load a JSON (`.json`) or YAML file holding a map of variable
+/// names to values, as used by `--vars-file`.
+pub fn load_vars_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<String, serde_json::Value>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read vars file: {}", path.display()))?;
+    let vars = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&content).context(format!(
+            "Failed to parse JSON vars file: {}",
+            path.display()
+        ))?
+    } else {
+        serde_yaml::from_str(&content).context(format!(
+            "Failed to parse YAML vars file: {}",
+            path.display()
+        ))?
+    };
+    Ok(vars)
+}
+
+/// Parse a `--var KEY=VALUE` assignment. The value is taken as
+/// JSON when it parses (`count=5` is a number), otherwise as a
+/// plain string (`name=alice`).
+pub fn parse_var(text: &str) -> Result<(String, serde_json::Value)> {
+    let (key, value) = text
+        .split_once('=')
+        .filter(|(key, _)| !key.trim().is_empty())
+        .context(format!(
+            "invalid variable '{text}' (expected KEY=VALUE)"
+        ))?;
+    let value = serde_json::from_str(value)
+        .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+    Ok((key.trim().to_string(), value))
+}
+
+/// Return `true` for directories a recursive scan never enters:
+/// hidden ones such as `.git` and Cargo's `target`.
+fn is_skipped_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.') || n == "target")
+}
+
+/// Return `true` when `path` contains glob metacharacters (`*`,
+/// `?`, or `[`).
+pub fn is_glob_pattern(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Expand a glob pattern into the scenario files it matches,
+/// sorted by path.
+///
+/// `*` and `?` match within one path component, `[abc]` /
+/// `[a-z]` / `[!abc]` match one character from a set, and a `**`
+/// component matches any number of directories. Matches that are
+/// not scenario files (see [`is_scenario_file`]) are ignored, and
+/// a pattern matching no scenario file is an error.
+pub fn expand_glob(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let mut base = PathBuf::new();
+    let mut components = pattern.components().peekable();
+    while let Some(component) = components.peek() {
+        if is_glob_pattern(Path::new(component.as_os_str())) {
+            break;
+        }
+        base.push(component);
+        components.next();
+    }
+    let rest: Vec<String> = components
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+
+    let mut files = Vec::new();
+    glob_walk(&base, &rest, &mut files);
+    files.retain(|path| path.is_file() && is_scenario_file(path));
+    files.sort();
+    files.dedup();
+    if files.is_empty() {
+        anyhow::bail!(
+            "Glob pattern matched no scenario files: {}",
+            pattern.display()
+        );
+    }
+    Ok(files)
+}
+
+/// Collect the paths under `dir` matching the `rest` components.
+fn glob_walk(dir: &Path, rest: &[String], out: &mut Vec<PathBuf>) {
+    let Some((first, tail)) = rest.split_first() else {
+        out.push(dir.to_path_buf());
+        return;
+    };
+    let read_from = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    if first == "**" {
+        glob_walk(dir, tail, out);
+    } else if !is_glob_pattern(Path::new(first)) {
+        let path = dir.join(first);
+        if path.exists() {
+            glob_walk(&path, tail, out);
+        }
+        return;
+    }
+    let Ok(entries) = fs::read_dir(read_from) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = dir.join(&name);
+        if first == "**" {
+            if path.is_dir() {
+                glob_walk(&path, rest, out);
+            }
+        } else if wildcard_match(first, &name) {
+            glob_walk(&path, tail, out);
+        }
+    }
+}
+
+/// Match one path component against a glob component.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => {
+                (0..=name.len()).any(|i| matches(rest, &name[i..]))
+            }
+            Some(('?', rest)) => {
+                !name.is_empty() && matches(rest, &name[1..])
+            }
+            Some(('[', rest)) => {
+                let Some(end) = rest.iter().skip(1).position(|&c| c == ']')
+                else {
+                    return name.first() == Some(&'[')
+                        && matches(rest, &name[1..]);
+                };
+                let (class, rest) = (&rest[..=end], &rest[end + 2..]);
+                let Some(&c) = name.first() else {
+                    return false;
+                };
+                let (negate, class) = match class.split_first() {
+                    Some(('!', class)) => (true, class),
+                    _ => (false, class),
+                };
+                let mut hit = false;
+                let mut i = 0;
+                while i < class.len() {
+                    if i + 2 < class.len() && class[i + 1] == '-' {
+                        hit |= class[i] <= c && c <= class[i + 2];
+                        i += 3;
+                    } else {
+                        hit |= class[i] == c;
+                        i += 1;
+                    }
+                }
+                hit != negate && matches(rest, &name[1..])
+            }
+            Some((&p, rest)) => {
+                name.first() == Some(&p) && matches(rest, &name[1..])
+            }
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches(&pattern, &name)
+}
+
 /// Return `true` when the path ends with `.scenario.md`.
 fn is_markdown_scenario(path: &Path) -> bool {
     path.file_name()
@@ -202,6 +551,46 @@ mod tests {
         assert!(!is_markdown_scenario(Path::new("scenario.md.bak")));
     }
 
+    // ── glob expansion ──────────────────────────────────
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.yaml", "a.yaml"));
+        assert!(!wildcard_match("*.yaml", "a.yml"));
+        assert!(wildcard_match("user?.yaml", "user1.yaml"));
+        assert!(wildcard_match("[ab]*.md", "b.scenario.md"));
+        assert!(!wildcard_match("[!ab]*.md", "b.scenario.md"));
+        assert!(wildcard_match("[0-9].yml", "7.yml"));
+    }
+
+    #[test]
+    fn test_expand_glob_recurses_and_filters() {
+        let dir = tempdir_with_files(&[
+            ("top.scenario.md", ""),
+            ("notes.md", ""),
+        ]);
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::write(dir.path().join("a/b/deep.scenario.md"), "")
+            .unwrap();
+        std::fs::write(dir.path().join("a/flat.yaml"), "").unwrap();
+
+        let files =
+            expand_glob(&dir.path().join("**/*.scenario.md")).unwrap();
+        assert_eq!(
+            files,
+            [
+                dir.path().join("a/b/deep.scenario.md"),
+                dir.path().join("top.scenario.md"),
+            ]
+        );
+
+        let files = expand_glob(&dir.path().join("*/*")).unwrap();
+        assert_eq!(files, [dir.path().join("a/flat.yaml")]);
+
+        let err = expand_glob(&dir.path().join("*.yml")).unwrap_err();
+        assert!(err.to_string().contains("matched no scenario files"));
+    }
+
     // ── load_scenarios_from_dir (filesystem tests) ──────
 
     #[test]
@@ -224,7 +613,7 @@ mod tests {
             ("d.md", "# Not a scenario\n"),
         ]);
 
-        let mgr = TestConfigManager::new();
+        let mut mgr = TestConfigManager::new();
         let scenarios = mgr.load_scenarios_from_dir(dir.path()).unwrap();
 
         assert_eq!(
@@ -242,11 +631,127 @@ mod tests {
     fn test_load_from_dir_ignores_plain_md() {
         let dir = tempdir_with_files(&[("readme.md", "# Just a readme\n")]);
 
-        let mgr = TestConfigManager::new();
+        let mut mgr = TestConfigManager::new();
         let scenarios = mgr.load_scenarios_from_dir(dir.path()).unwrap();
         assert!(scenarios.is_empty(), "Plain .md should not be loaded");
     }
 
+    #[test]
+    fn test_load_from_dir_records_skipped_files() {
+        let dir = tempdir_with_files(&[
+            (
+                "good.yaml",
+                "name: good\nsteps:\n  - name: s\n    \
+                 request:\n      method: GET\n      url: /t\n    \
+                 expect:\n      status: 200\n",
+            ),
+            ("broken.yaml", "name: [unterminated\n"),
+        ]);
+
+        let mut mgr = TestConfigManager::new();
+        let scenarios = mgr.load_scenarios_from_dir(dir.path()).unwrap();
+
+        assert_eq!(scenarios.len(), 1);
+        let skipped = mgr.skipped_files();
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].path.ends_with("broken.yaml"));
+        assert!(!skipped[0].error.is_empty());
+    }
+
+    #[test]
+    fn test_load_from_dir_recurses_unless_disabled() {
+        let scenario = "name: nested\nsteps: []\n";
+        let dir =
+            tempdir_with_files(&[("top.yaml", "name: top\nsteps: []\n")]);
+        for sub in ["users", ".hidden", "target"] {
+            std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+            std::fs::write(dir.path().join(sub).join("s.yaml"), scenario)
+                .unwrap();
+            std::fs::write(dir.path().join(sub).join("notes.md"), "")
+                .unwrap();
+        }
+
+        let mut mgr = TestConfigManager::new();
+        let files = mgr.scenario_files_in_dir(dir.path()).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files.contains(&dir.path().join("users/s.yaml")));
+
+        mgr.set_recursive(false);
+        let scenarios = mgr.load_scenarios_from_dir(dir.path()).unwrap();
+        assert_eq!(scenarios.len(), 1);
+        assert_eq!(scenarios[0].name, "top");
+    }
+
+    #[test]
+    fn test_load_all_scenarios_dedups_overlapping_paths() {
+        let dir = tempdir_with_files(&[]);
+        let nested = dir.path().join("tests/scenarios");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("a.yaml"), "name: a\nsteps: []\n")
+            .unwrap();
+
+        let mut mgr = TestConfigManager::new();
+        mgr.test_paths = vec![nested, dir.path().to_path_buf()];
+        assert_eq!(mgr.load_all_scenarios().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_load_multi_document_yaml_file() {
+        let dir = tempdir_with_files(&[(
+            "many.yaml",
+            "name: first\nsteps: []\n---\nname: second\nsteps: []\n",
+        )]);
+
+        let mut mgr = TestConfigManager::new();
+        let scenarios = mgr.load_scenarios_from_dir(dir.path()).unwrap();
+        let names: Vec<&str> =
+            scenarios.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["first", "second"]);
+    }
+
+    #[test]
+    fn test_multi_document_error_names_document() {
+        let dir = tempdir_with_files(&[(
+            "many.yaml",
+            "name: first\nsteps: []\n---\nsteps: []\n",
+        )]);
+
+        let mgr = TestConfigManager::new();
+        let err = mgr
+            .load_scenarios_from_file(dir.path().join("many.yaml"))
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("YAML document 2"));
+    }
+
+    #[test]
+    fn test_load_vars_file_json_and_yaml() {
+        let dir = tempdir_with_files(&[
+            ("vars.json", r#"{"user": "alice", "count": 2}"#),
+            ("vars.yaml", "user: bob\ntags: [a, b]\n"),
+            ("bad.yaml", "- not\n- a map\n"),
+        ]);
+
+        let json = load_vars_file(dir.path().join("vars.json")).unwrap();
+        assert_eq!(json["count"], 2);
+        let yaml = load_vars_file(dir.path().join("vars.yaml")).unwrap();
+        assert_eq!(yaml["user"], "bob");
+        assert_eq!(yaml["tags"], serde_json::json!(["a", "b"]));
+        assert!(load_vars_file(dir.path().join("bad.yaml")).is_err());
+    }
+
+    #[test]
+    fn test_parse_var_prefers_json() {
+        let parse = |text| parse_var(text).unwrap();
+        assert_eq!(parse("count=5"), ("count".into(), 5.into()));
+        assert_eq!(parse("name=alice"), ("name".into(), "alice".into()));
+        assert_eq!(parse("flag=true").1, true);
+        assert_eq!(parse("ids=[1,2]").1, serde_json::json!([1, 2]));
+        assert_eq!(parse("q=a=b").1, "a=b");
+        assert_eq!(parse("empty=").1, "");
+        assert!(parse_var("novalue").is_err());
+        assert!(parse_var("=5").is_err());
+    }
+
     // ── helper ──────────────────────────────────────────
 
     fn tempdir_with_files(files: &[(&str, &str)]) -> tempfile::TempDir {
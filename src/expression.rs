@@ -2,7 +2,10 @@
 //! `test:` assertions and `bind:` variable resolution.
 
 use anyhow::{anyhow, Result};
-use cel::{Context, Program};
+use base64::prelude::{
+    Engine as _, BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD,
+};
+use cel::{Context, ExecutionError, Program, ResolveResult};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -75,6 +78,9 @@ fn compile(expr: &str) -> Result<Program> {
         .map_err(|e| anyhow!("CEL compile error for '{processed}': {e}"))
 }
 
+/// Name of the shorthand alias for `current.res.body`.
+const BODY_ALIAS: &str = "body";
+
 fn build_context<'a>(vars: &HashMap<String, Value>) -> Result<Context<'a>> {
     let mut context = Context::default();
 
@@ -88,6 +94,18 @@ fn build_context<'a>(vars: &HashMap<String, Value>) -> Result<Context<'a>> {
             })?;
     }
 
+    // `body` aliases `current.res.body`, unless a variable with
+    // that name already exists
+    if !vars.contains_key(BODY_ALIAS) {
+        if let Some(body) =
+            vars.get("current").and_then(|c| c.pointer("/res/body"))
+        {
+            context.add_variable(BODY_ALIAS, body.clone()).map_err(
+                |e| anyhow!("Failed to add 'body' to CEL context: {e}"),
+            )?;
+        }
+    }
+
     // Register runn-compatible custom functions
     register_custom_functions(&mut context);
 
@@ -130,6 +148,93 @@ fn register_custom_functions(context: &mut Context<'_>) {
         Arc::new(t.to_string())
     });
 
+    // jsonpath(obj, path) - value at a dotted JSON path such as
+    // `$.items.0.id`, or null when the path does not exist
+    context.add_function(
+        "jsonpath",
+        |obj: cel::Value, path: Arc<String>| -> ResolveResult {
+            let json = obj.json().map_err(|e| {
+                ExecutionError::function_error("jsonpath", e.to_string())
+            })?;
+            let found = crate::validator::get_by_json_path(&json, &path)
+                .cloned()
+                .unwrap_or(Value::Null);
+            cel::to_value(found).map_err(|e| {
+                ExecutionError::function_error("jsonpath", e.to_string())
+            })
+        },
+    );
+
+    // now() - current unix timestamp in seconds
+    context
+        .add_function("now", || -> i64 { chrono::Utc::now().timestamp() });
+
+    // parse_time(str) - RFC 3339 timestamp to unix seconds
+    context.add_function(
+        "parse_time",
+        |s: Arc<String>| -> Result<i64, ExecutionError> {
+            chrono::DateTime::parse_from_rfc3339(&s)
+                .map(|t| t.timestamp())
+                .map_err(|e| {
+                    ExecutionError::function_error(
+                        "parse_time",
+                        format!("invalid RFC 3339 timestamp '{s}': {e}"),
+                    )
+                })
+        },
+    );
+
+    // base64decode(str) - decode standard base64 into a UTF-8 string
+    context.add_function(
+        "base64decode",
+        |s: Arc<String>| -> Result<Arc<String>, ExecutionError> {
+            let bytes =
+                BASE64_STANDARD.decode(s.as_bytes()).map_err(|e| {
+                    ExecutionError::function_error(
+                        "base64decode",
+                        format!("invalid base64: {e}"),
+                    )
+                })?;
+            String::from_utf8(bytes).map(Arc::new).map_err(|e| {
+                ExecutionError::function_error(
+                    "base64decode",
+                    format!("decoded bytes are not UTF-8: {e}"),
+                )
+            })
+        },
+    );
+
+    // jwt_payload(token) - claims of a JWT as a map (the signature
+    // is not verified)
+    context.add_function(
+        "jwt_payload",
+        |token: Arc<String>| -> ResolveResult {
+            let error = |message: String| {
+                ExecutionError::function_error("jwt_payload", message)
+            };
+            let segments: Vec<&str> = token.split('.').collect();
+            let [_, payload, _] = segments[..] else {
+                return Err(error(format!(
+                "malformed JWT: expected 3 '.'-separated segments, got {}",
+                segments.len()
+            )));
+            };
+            let bytes = BASE64_URL_SAFE_NO_PAD
+                .decode(payload.trim_end_matches('='))
+                .map_err(|e| {
+                    error(format!("payload is not base64url: {e}"))
+                })?;
+            let claims: Value = serde_json::from_slice(&bytes)
+                .map_err(|e| error(format!("payload is not JSON: {e}")))?;
+            if !claims.is_object() {
+                return Err(error(
+                    "payload is not a JSON object".to_string(),
+                ));
+            }
+            cel::to_value(claims).map_err(|e| error(e.to_string()))
+        },
+    );
+
     // urlencode(str) - URL-encode a string
     context.add_function("urlencode", |s: Arc<String>| -> Arc<String> {
         Arc::new(
@@ -176,6 +281,30 @@ mod tests {
         assert!(!evaluate_test("x < 5", &vars).unwrap());
     }
 
+    #[test]
+    fn test_body_alias_for_current_res_body() {
+        let vars = make_vars(vec![(
+            "current",
+            json!({"res": {"status": 200, "body": {"items": [{"id": 7}]}}}),
+        )]);
+        assert!(evaluate_test("body.items[0].id == 7", &vars).unwrap());
+        assert!(evaluate_test("current.res.body.items[0].id == 7", &vars)
+            .unwrap());
+        assert_eq!(
+            resolve_value("body.items[0].id", &vars).unwrap(),
+            json!(7)
+        );
+    }
+
+    #[test]
+    fn test_body_variable_takes_precedence_over_alias() {
+        let vars = make_vars(vec![
+            ("body", json!("mine")),
+            ("current", json!({"res": {"body": {"x": 1}}})),
+        ]);
+        assert!(evaluate_test("body == 'mine'", &vars).unwrap());
+    }
+
     #[test]
     fn test_logical_operators() {
         let vars = make_vars(vec![("x", json!(10)), ("y", json!(20))]);
@@ -312,6 +441,94 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_jsonpath_function() {
+        let vars = make_vars(vec![(
+            "data",
+            json!({"items": [{"id": 1}, {"id": 2, "tags": ["a", "b"]}]}),
+        )]);
+        let v = resolve_value(r#"jsonpath(data, "$.items.1.tags")"#, &vars)
+            .unwrap();
+        assert_eq!(v, json!(["a", "b"]));
+        assert!(evaluate_test(
+            r#"jsonpath(data, "items.0.id") == 1"#,
+            &vars
+        )
+        .unwrap());
+        let v = resolve_value(r#"jsonpath(data, "items.5.id")"#, &vars)
+            .unwrap();
+        assert_eq!(v, Value::Null);
+    }
+
+    #[test]
+    fn test_time_functions() {
+        let created = chrono::Utc::now() - chrono::Duration::seconds(5);
+        let vars = make_vars(vec![
+            ("created_at", json!(created.to_rfc3339())),
+            ("epoch", json!("1970-01-01T00:01:00+00:00")),
+        ]);
+        assert!(evaluate_test(
+            "now() - parse_time(created_at) < 60 \
+             && now() - parse_time(created_at) >= 5",
+            &vars
+        )
+        .unwrap());
+        let v = resolve_value("parse_time(epoch)", &vars).unwrap();
+        assert_eq!(v, json!(60));
+    }
+
+    #[test]
+    fn test_parse_time_invalid_is_execution_error() {
+        let vars = make_vars(vec![("ts", json!("yesterday"))]);
+        let err = evaluate_test("parse_time(ts) > 0", &vars).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("CEL execution error"), "{message}");
+        assert!(message.contains("'yesterday'"), "{message}");
+    }
+
+    #[test]
+    fn test_base64decode_function() {
+        let vars = make_vars(vec![("s", json!("aGVsbG8gd29ybGQ="))]);
+        let v = resolve_value("base64decode(s)", &vars).unwrap();
+        assert_eq!(v, json!("hello world"));
+
+        let vars = make_vars(vec![("s", json!("not base64!"))]);
+        let err = resolve_value("base64decode(s)", &vars).unwrap_err();
+        assert!(err.to_string().contains("invalid base64"), "{err}");
+    }
+
+    #[test]
+    fn test_jwt_payload_function() {
+        // {"alg":"HS256"} . {"sub":"user_1","admin":true} . signature
+        let token = "eyJhbGciOiJIUzI1NiJ9.\
+                     eyJzdWIiOiJ1c2VyXzEiLCJhZG1pbiI6dHJ1ZX0.c2ln";
+        let vars = make_vars(vec![("token", json!(token))]);
+        assert!(evaluate_test(
+            r#"jwt_payload(token).sub == "user_1" && jwt_payload(token).admin"#,
+            &vars
+        )
+        .unwrap());
+        let v = resolve_value("jwt_payload(token)", &vars).unwrap();
+        assert_eq!(v, json!({"sub": "user_1", "admin": true}));
+    }
+
+    #[test]
+    fn test_jwt_payload_malformed_token_is_execution_error() {
+        for (token, expected) in [
+            ("not-a-jwt", "expected 3 '.'-separated segments, got 1"),
+            ("a.!!!.c", "payload is not base64url"),
+            ("a.bm90IGpzb24.c", "payload is not JSON"),
+            ("a.WzFd.c", "payload is not a JSON object"),
+        ] {
+            let vars = make_vars(vec![("token", json!(token))]);
+            let err =
+                resolve_value("jwt_payload(token)", &vars).unwrap_err();
+            let message = err.to_string();
+            assert!(message.contains("CEL execution error"), "{message}");
+            assert!(message.contains(expected), "{message}");
+        }
+    }
+
     #[test]
     fn test_urlencode_function() {
         let vars = make_vars(vec![("q", json!("hello world&foo=bar"))]);
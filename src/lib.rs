@@ -8,14 +8,21 @@ pub mod config;
 pub mod expression;
 pub mod markdown_parser;
 pub mod model;
+pub mod redact;
+pub mod reporting;
 pub mod runn_parser;
 pub mod runner;
+pub mod soak;
 pub mod sse;
+pub mod suite;
+pub mod tls;
 pub mod validator;
+pub mod xml;
 
 pub use config::*;
 pub use model::*;
 pub use runner::*;
+pub use suite::*;
 pub use validator::*;
 
 /// TODO: add English documentation
@@ -29,8 +36,8 @@ pub fn add(left: u64, right: u64) -> u64 {
 mod tests {
     use super::*;
     use model::{
-        HttpMethod, HttpRequest, ResponseExpectation, TestScenario,
-        TestStep,
+        BodyType, HttpMethod, HttpRequest, ResponseExpectation,
+        TestScenario, TestStep,
     };
     use serde_json::json;
     use std::collections::HashMap;
@@ -52,24 +59,25 @@ mod tests {
                     headers: HashMap::new(),
                     query: HashMap::new(),
                     body: None,
+                    body_file: None,
+                    compress: None,
+                    body_type: BodyType::Json,
                 },
                 expect: ResponseExpectation {
-                    status: 200,
-                    headers: HashMap::new(),
-                    json: HashMap::new(),
-                    json_lengths: HashMap::new(),
-                    schema: None,
-                    contains: vec![],
-                    json_eq: None,
-                    json_ignore_fields: vec![],
-                    sse: None,
+                    status: Some(200.into()),
+                    ..Default::default()
                 },
                 save: HashMap::new(),
+                save_regex: HashMap::new(),
                 condition: None,
                 test: None,
                 bind: HashMap::new(),
                 loop_config: None,
+                retry: None,
                 include: None,
+                inject_request_id: None,
+                timeout: None,
+                vars: HashMap::new(),
             }],
             vars: HashMap::new(),
             config: model::TestConfig {
@@ -77,7 +85,14 @@ mod tests {
                 headers: HashMap::new(),
                 timeout: 30,
                 continue_on_failure: false,
+                ..Default::default()
             },
+            requires_env: vec![],
+            assert: None,
+            weight: None,
+            environments: HashMap::new(),
+            setup: Vec::new(),
+            teardown: Vec::new(),
         };
 
         // TODO: add English comment
@@ -33,7 +33,7 @@ use std::collections::HashMap;
 use anyhow::{bail, Context, Result};
 use serde::Deserialize;
 
-use crate::model::{TestConfig, TestScenario, TestStep};
+use crate::model::{AuthConfig, TestConfig, TestScenario, TestStep};
 
 /// Intermediate representation for the YAML front matter.
 #[derive(Debug, Deserialize)]
@@ -47,6 +47,18 @@ struct FrontMatter {
     config: TestConfig,
     #[serde(default)]
     tags: Vec<String>,
+    #[serde(default)]
+    requires_env: Vec<String>,
+    #[serde(default)]
+    assert: Option<String>,
+    #[serde(default)]
+    weight: Option<u32>,
+    #[serde(default)]
+    environments: HashMap<String, HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    setup: Vec<TestStep>,
+    #[serde(default)]
+    teardown: Vec<TestStep>,
 }
 
 /// Intermediate struct for code-block content.
@@ -77,6 +89,20 @@ struct MergeableConfig {
     timeout: Option<u64>,
     #[serde(default)]
     continue_on_failure: Option<bool>,
+    #[serde(default)]
+    numeric_loose: Option<bool>,
+    #[serde(default)]
+    coerce_numeric_strings: Option<bool>,
+    #[serde(default)]
+    inject_request_id: Option<String>,
+    #[serde(default)]
+    base_path: Option<String>,
+    #[serde(default)]
+    auth: Option<AuthConfig>,
+    #[serde(default)]
+    insecure_tls: Option<bool>,
+    #[serde(default)]
+    fail_on_teardown: Option<bool>,
 }
 
 /// A parsed fenced code block together with its starting
@@ -130,6 +156,12 @@ pub fn parse_markdown_scenario(input: &str) -> Result<TestScenario> {
         steps: all_steps,
         vars: fm.vars,
         config: merged_config,
+        requires_env: fm.requires_env,
+        assert: fm.assert,
+        weight: fm.weight,
+        environments: fm.environments,
+        setup: fm.setup,
+        teardown: fm.teardown,
     })
 }
 
@@ -157,6 +189,27 @@ fn merge_config(dst: &mut TestConfig, src: &MergeableConfig) {
     if let Some(continue_on_failure) = src.continue_on_failure {
         dst.continue_on_failure = continue_on_failure;
     }
+    if let Some(numeric_loose) = src.numeric_loose {
+        dst.numeric_loose = numeric_loose;
+    }
+    if let Some(coerce) = src.coerce_numeric_strings {
+        dst.coerce_numeric_strings = coerce;
+    }
+    if let Some(ref header) = src.inject_request_id {
+        dst.inject_request_id = Some(header.clone());
+    }
+    if let Some(ref base_path) = src.base_path {
+        dst.base_path = Some(base_path.clone());
+    }
+    if let Some(ref auth) = src.auth {
+        dst.auth = Some(auth.clone());
+    }
+    if let Some(insecure_tls) = src.insecure_tls {
+        dst.insecure_tls = insecure_tls;
+    }
+    if let Some(fail_on_teardown) = src.fail_on_teardown {
+        dst.fail_on_teardown = fail_on_teardown;
+    }
 }
 
 /// Extract YAML front matter enclosed between `---` delimiters.
@@ -313,6 +366,29 @@ steps:
         assert_eq!(scenario.vars.get("operator_id").unwrap(), "tn_test");
     }
 
+    #[test]
+    fn test_front_matter_tags_preserved() {
+        let input = r#"---
+name: tagged
+tags:
+  - auth
+  - billing
+---
+
+```yaml scenario
+steps:
+  - name: s
+    request:
+      method: GET
+      url: /t
+    expect:
+      status: 200
+```
+"#;
+        let scenario = parse_markdown_scenario(input).unwrap();
+        assert_eq!(scenario.tags, vec!["auth", "billing"]);
+    }
+
     #[test]
     fn test_missing_front_matter() {
         let input = "# No front matter\n\nSome text\n";
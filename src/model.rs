@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// TODO: add English documentation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,14 +15,42 @@ pub struct TestScenario {
     /// Tags for filtering and grouping scenarios.
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Steps run once before `steps`. If one fails, the main steps
+    /// are skipped and the scenario fails.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub setup: Vec<TestStep>,
     /// TODO: add English documentation
     pub steps: Vec<TestStep>,
+    /// Steps run once after `steps`, even when setup or a main
+    /// step failed. Every teardown step runs; their failures are
+    /// reported but only fail the scenario with
+    /// [`TestConfig::fail_on_teardown`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teardown: Vec<TestStep>,
     /// TODO: add English documentation
     #[serde(default)]
     pub vars: HashMap<String, serde_json::Value>,
     /// TODO: add English documentation
     #[serde(default)]
     pub config: TestConfig,
+    /// Environment variables that must be set (and non-empty)
+    /// before the scenario runs.
+    #[serde(default)]
+    pub requires_env: Vec<String>,
+    /// CEL expression evaluated after every step has passed, with
+    /// access to `steps` and `vars`. A falsy result fails the
+    /// scenario.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assert: Option<String>,
+    /// Relative selection weight in `--soak` runs (default 1;
+    /// `0` never selects the scenario).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+    /// Per-environment variable sets, keyed by environment name.
+    /// The set selected with `--env` is merged over `vars`, so
+    /// requests and expectations can differ per environment.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub environments: HashMap<String, HashMap<String, serde_json::Value>>,
 }
 
 /// A single step in a test scenario.
@@ -39,11 +68,22 @@ pub struct TestStep {
     pub request: HttpRequest,
     /// Declarative response expectations (muon native).
     pub expect: ResponseExpectation,
-    /// Save response values into variables (muon native, JSON
-    /// path based).
+    /// Save response values into variables (muon native). Values
+    /// are JSON paths into the body, `header.<Name>` (or
+    /// `headers.<Name>`) for a response header, or
+    /// `__status__` / `__body__` / `__headers__` for the whole
+    /// status code, raw body or header map.
     #[serde(default)]
     pub save: HashMap<String, String>,
-    /// Condition for skipping the step.
+    /// Save the first capture group of a regex applied to a
+    /// response value (e.g. the id in a `Location` header).
+    #[serde(default)]
+    pub save_regex: HashMap<String, SaveRegex>,
+    /// Run the step only when this holds: `true`/`false` after
+    /// placeholder expansion, or else a CEL expression over the
+    /// variables (e.g. `steps.login.res.status == 200`). A step
+    /// whose condition fails to evaluate fails with
+    /// `condition error: ...` instead of being skipped.
     #[serde(default)]
     pub condition: Option<String>,
 
@@ -64,9 +104,42 @@ pub struct TestStep {
     #[serde(default)]
     pub loop_config: Option<LoopConfig>,
 
+    /// Re-run the whole step when it fails, including `expect` /
+    /// `test` validation failures. Stops at the first success.
+    #[serde(default)]
+    pub retry: Option<RetryConfig>,
+
     /// Include an external scenario file (runn-compatible).
     #[serde(default)]
     pub include: Option<IncludeConfig>,
+
+    /// Set to `false` to skip `config.inject_request_id` for this
+    /// step.
+    #[serde(default)]
+    pub inject_request_id: Option<bool>,
+
+    /// Request timeout in seconds for this step, overriding
+    /// `config.timeout`. `0` falls back to the config value.
+    #[serde(default)]
+    pub timeout: Option<u64>,
+
+    /// Variables visible only while this step runs. They take
+    /// precedence over scenario and saved variables of the same
+    /// name, which are restored once the step finishes.
+    #[serde(default)]
+    pub vars: HashMap<String, serde_json::Value>,
+}
+
+/// Regex extraction for [`TestStep::save_regex`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveRegex {
+    /// Source value: `header.<Name>` (or `headers.<Name>`) for a
+    /// response header, `body` for the raw body, or a JSON path
+    /// into the response body.
+    pub from: String,
+    /// Regex with at least one capture group; the first group is
+    /// saved as a string.
+    pub pattern: String,
 }
 
 /// Configuration for including an external scenario file.
@@ -101,6 +174,19 @@ pub struct LoopConfig {
     pub max_interval: Option<f64>,
 }
 
+/// Retry configuration for [`TestStep::retry`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Number of retries after the first attempt.
+    pub count: u32,
+    /// Delay before the first retry in seconds.
+    #[serde(default = "default_loop_interval")]
+    pub interval: f64,
+    /// Multiplier applied to the delay after each retry.
+    #[serde(default)]
+    pub backoff: Option<f64>,
+}
+
 fn default_loop_count() -> u32 {
     3
 }
@@ -125,6 +211,58 @@ pub struct HttpRequest {
     /// TODO: add English documentation
     #[serde(default)]
     pub body: Option<serde_json::Value>,
+    /// File whose contents become the body instead of `body`. A
+    /// `.json` file is parsed, then its strings expanded like an
+    /// inline `body` and encoded per `body_type`; any other file
+    /// is sent as raw text with placeholders expanded in place.
+    /// Relative paths resolve against the scenario file's
+    /// directory ([`TestConfig::base_dir`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_file: Option<String>,
+    /// Compress the (expanded) body before sending and set
+    /// `Content-Encoding` accordingly. `Content-Type` keeps the
+    /// uncompressed media type.
+    #[serde(default)]
+    pub compress: Option<Compression>,
+    /// How `body` is encoded on the wire.
+    #[serde(default)]
+    pub body_type: BodyType,
+}
+
+/// Request body encoding.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyType {
+    /// JSON, `Content-Type: application/json`.
+    #[default]
+    Json,
+    /// `application/x-www-form-urlencoded` from a flat object.
+    Form,
+    /// `multipart/form-data` from an object; a `{ file: path }`
+    /// value (optionally with `content_type`) uploads that file.
+    Multipart,
+}
+
+/// Request body compression algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// gzip (RFC 1952), `Content-Encoding: gzip`.
+    Gzip,
+    /// zlib-wrapped deflate (RFC 1950), `Content-Encoding: deflate`.
+    Deflate,
+}
+
+impl Compression {
+    /// Value for the `Content-Encoding` header.
+    pub fn encoding(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Deflate => "deflate",
+        }
+    }
 }
 
 /// TODO: add English documentation
@@ -141,35 +279,360 @@ pub enum HttpMethod {
 }
 
 /// TODO: add English documentation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResponseExpectation {
-    /// TODO: add English documentation
-    #[serde(default = "default_status_code")]
-    pub status: u16,
+    /// Accepted status codes: a single code, a list such as
+    /// `[200, 204]`, or a range string such as `"2xx"` or
+    /// `"200-204"`. Defaults to 200 unless `ok` is set; an explicit
+    /// value always takes precedence over `ok`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<StatusExpectation>,
+    /// Accept any 2xx status (`200..=299`) when `status` is not
+    /// given.
+    #[serde(default)]
+    pub ok: bool,
     /// TODO: add English documentation
     #[serde(default)]
     pub headers: HashMap<String, String>,
+    /// Lenient header checks: each header (name matched
+    /// case-insensitively) must contain the given text, compared
+    /// case-insensitively, e.g. `content-type: application/json`
+    /// accepts `application/json; charset=utf-8`.
+    #[serde(default)]
+    pub headers_contain: HashMap<String, String>,
     /// TODO: add English documentation
     #[serde(default)]
     pub json: HashMap<String, serde_json::Value>,
     /// TODO: add English documentation
     #[serde(default)]
     pub json_lengths: HashMap<String, usize>,
-    /// TODO: add English documentation
+    /// Inequality checks: the value at each JSON path must differ
+    /// from the given value. A missing path counts as different.
+    #[serde(default)]
+    pub json_ne: HashMap<String, serde_json::Value>,
+    /// JSON paths that must be present with a non-null value.
+    #[serde(default)]
+    pub json_exists: Vec<String>,
+    /// JSON paths that must not be present.
+    #[serde(default)]
+    pub json_absent: Vec<String>,
+    /// Enum checks: the value at each JSON path must equal one of
+    /// the listed values.
+    #[serde(default)]
+    pub json_in: HashMap<String, Vec<serde_json::Value>>,
+    /// Approximate numeric checks: the number at each JSON path
+    /// must be within `epsilon` of `value`.
+    #[serde(default)]
+    pub json_approx: HashMap<String, ApproxExpectation>,
+    /// Structural checks on double-encoded JSON: the value at each
+    /// path must be a string that parses to the expected JSON
+    /// (compared like `json_eq`, so key order and whitespace in
+    /// the string do not matter).
+    #[serde(default)]
+    pub json_decoded: HashMap<String, serde_json::Value>,
+    /// Presence/absence checks on JSON paths, without values.
+    #[serde(default)]
+    pub shape: Option<ShapeExpectation>,
+    /// CSV body checks: row count, header row, and cell values.
+    #[serde(default)]
+    pub csv: Option<CsvExpectation>,
+    /// XML body checks: XPath-like paths (`/a/b`, `//item[2]`,
+    /// `/a/@id`) mapped to the expected trimmed text or attribute
+    /// value. See [`crate::xml::select`] for the path syntax.
+    #[serde(default)]
+    pub xpath: HashMap<String, String>,
+    /// Peer certificate checks for HTTPS requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert: Option<CertExpectation>,
+    /// JSON Schema (draft 2020-12) the response body must
+    /// satisfy.
     #[serde(default)]
     pub schema: Option<serde_json::Value>,
-    /// TODO: add English documentation
+    /// Strings the response body must contain. Entries prefixed
+    /// with `re:` are regexes; `\re:` escapes a literal `re:`.
     #[serde(default)]
     pub contains: Vec<String>,
+    /// Regexes the raw response body must match, mapped to the
+    /// named capture groups to save as variables. `null` saves
+    /// every named group.
+    #[serde(default)]
+    pub matches: HashMap<String, Option<Vec<String>>>,
     /// Full JSON equality check for REST responses.
     #[serde(default)]
     pub json_eq: Option<serde_json::Value>,
-    /// Fields to exclude from `json_eq` comparison.
+    /// Fields to exclude from `json_eq` comparison. Same pattern
+    /// syntax as [`SseEventExpectation::ignore_fields`].
     #[serde(default)]
     pub json_ignore_fields: Vec<String>,
     /// SSE event stream expectations
     #[serde(default)]
     pub sse: Option<SseExpectation>,
+    /// How the non-status expectation groups combine. `status` is
+    /// always required. The groups are `headers` (with
+    /// `headers_contain`), `cert`, `http_version`,
+    /// `max_duration_ms`, `rate_limit`, `json` (with `json_ne`,
+    /// `json_exists`, `json_absent`, `json_in`, `json_approx` and
+    /// `json_lengths`), `json_eq`, `shape`, `json_decoded`,
+    /// `xpath`, `csv`, `schema`, `contains`, `matches` and `sse`.
+    #[serde(default, rename = "match")]
+    pub match_mode: MatchMode,
+    /// Latency percentile limits across the iterations of a looped
+    /// step. Ignored for steps without `loop`.
+    #[serde(default)]
+    pub loop_timing: Option<LoopTimingExpectation>,
+    /// Expected negotiated HTTP version, e.g. `HTTP/1.1`. The client is
+    /// built without HTTP/2 support, so only `HTTP/1.0` and `HTTP/1.1`
+    /// can be negotiated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<String>,
+    /// Maximum request round-trip time in milliseconds, measured
+    /// from sending the request until the body is read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_ms: Option<u64>,
+    /// Rate-limit header checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitExpectation>,
+}
+
+impl ResponseExpectation {
+    /// Return `true` if `status` satisfies `status` / `ok`.
+    pub fn status_matches(&self, status: u16) -> bool {
+        match &self.status {
+            Some(expected) => expected.matches(status),
+            None if self.ok => (200..=299).contains(&status),
+            None => status == default_status_code(),
+        }
+    }
+
+    /// Human-readable description of the accepted status codes.
+    pub fn expected_status_label(&self) -> String {
+        match &self.status {
+            Some(expected) => expected.to_string(),
+            None if self.ok => "2xx".to_string(),
+            None => default_status_code().to_string(),
+        }
+    }
+}
+
+/// Accepted status codes for [`ResponseExpectation::status`].
+///
+/// Deserializes from a number (`201`), a list of numbers
+/// (`[200, 204]`), or a string holding a code, a class (`"2xx"`),
+/// or an inclusive range (`"200-204"`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawStatus", into = "RawStatus")]
+pub enum StatusExpectation {
+    /// Exactly this code.
+    Code(u16),
+    /// Any of these codes.
+    OneOf(Vec<u16>),
+    /// Any code in this inclusive range.
+    Range(u16, u16),
+}
+
+impl StatusExpectation {
+    /// Return `true` if `status` is accepted.
+    pub fn matches(&self, status: u16) -> bool {
+        match self {
+            Self::Code(code) => status == *code,
+            Self::OneOf(codes) => codes.contains(&status),
+            Self::Range(low, high) => (*low..=*high).contains(&status),
+        }
+    }
+}
+
+impl From<u16> for StatusExpectation {
+    fn from(code: u16) -> Self {
+        Self::Code(code)
+    }
+}
+
+impl std::fmt::Display for StatusExpectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Code(code) => write!(f, "{code}"),
+            Self::OneOf(codes) => {
+                let codes: Vec<String> =
+                    codes.iter().map(u16::to_string).collect();
+                write!(f, "{}", codes.join(" or "))
+            }
+            Self::Range(low, high)
+                if low % 100 == 0 && *high == low + 99 =>
+            {
+                write!(f, "{}xx", low / 100)
+            }
+            Self::Range(low, high) => write!(f, "{low}-{high}"),
+        }
+    }
+}
+
+/// Serialized form of [`StatusExpectation`].
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawStatus {
+    Code(u16),
+    OneOf(Vec<u16>),
+    Text(String),
+}
+
+impl TryFrom<RawStatus> for StatusExpectation {
+    type Error = String;
+
+    fn try_from(raw: RawStatus) -> Result<Self, Self::Error> {
+        match raw {
+            RawStatus::Code(code) => Ok(Self::Code(code)),
+            RawStatus::OneOf(codes) if codes.is_empty() => {
+                Err("status list must not be empty".to_string())
+            }
+            RawStatus::OneOf(codes) => Ok(Self::OneOf(codes)),
+            RawStatus::Text(text) => {
+                parse_status_text(&text).ok_or_else(|| {
+                    format!(
+                        "invalid status '{text}' (expected a code, a \
+                         class like 2xx, or a range like 200-204)"
+                    )
+                })
+            }
+        }
+    }
+}
+
+impl From<StatusExpectation> for RawStatus {
+    fn from(status: StatusExpectation) -> Self {
+        match status {
+            StatusExpectation::Code(code) => RawStatus::Code(code),
+            StatusExpectation::OneOf(codes) => RawStatus::OneOf(codes),
+            range => RawStatus::Text(range.to_string()),
+        }
+    }
+}
+
+fn parse_status_text(text: &str) -> Option<StatusExpectation> {
+    let text = text.trim();
+    let lower = text.to_ascii_lowercase();
+    if let Some(class) = lower.strip_suffix("xx") {
+        let class: u16 =
+            class.parse().ok().filter(|c| (1..=5).contains(c))?;
+        return Some(StatusExpectation::Range(
+            class * 100,
+            class * 100 + 99,
+        ));
+    }
+    if let Some((low, high)) = text.split_once('-') {
+        let low: u16 = low.trim().parse().ok()?;
+        let high: u16 = high.trim().parse().ok()?;
+        return (low <= high)
+            .then_some(StatusExpectation::Range(low, high));
+    }
+    text.parse().ok().map(StatusExpectation::Code)
+}
+
+/// Expectations on the server's TLS certificate.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CertExpectation {
+    /// Fail if the certificate expires within this many days.
+    #[serde(default)]
+    pub expires_after_days: Option<u64>,
+    /// Substring the certificate issuer must contain.
+    #[serde(default)]
+    pub issuer_contains: Option<String>,
+}
+
+/// Rate-limit checks for [`ResponseExpectation::rate_limit`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitExpectation {
+    /// Exact value expected in the limit header.
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Minimum value allowed in the remaining-quota header.
+    #[serde(default)]
+    pub remaining_min: Option<u64>,
+    /// Name of the limit header (case-insensitive).
+    #[serde(default = "default_rate_limit_header")]
+    pub limit_header: String,
+    /// Name of the remaining-quota header (case-insensitive).
+    #[serde(default = "default_rate_remaining_header")]
+    pub remaining_header: String,
+}
+
+fn default_rate_limit_header() -> String {
+    "X-RateLimit-Limit".to_string()
+}
+
+fn default_rate_remaining_header() -> String {
+    "X-RateLimit-Remaining".to_string()
+}
+
+/// Expected number for [`ResponseExpectation::json_approx`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApproxExpectation {
+    /// Expected value.
+    pub value: f64,
+    /// Maximum allowed absolute difference.
+    pub epsilon: f64,
+}
+
+/// Structural contract for a JSON response: which dot-paths must,
+/// may, or must not be present, regardless of their values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShapeExpectation {
+    /// Paths that must be present (a `null` value counts).
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Paths that may be present. Never fails; documents the
+    /// contract alongside `required`.
+    #[serde(default)]
+    pub optional: Vec<String>,
+    /// Paths that must be absent.
+    #[serde(default)]
+    pub forbidden: Vec<String>,
+}
+
+/// Checks on a CSV response body. The first record is the header
+/// row; data rows are counted and addressed after it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CsvExpectation {
+    /// Number of data rows, excluding the header row.
+    #[serde(default)]
+    pub row_count: Option<usize>,
+    /// Expected column names, in order.
+    #[serde(default)]
+    pub headers: Option<Vec<String>>,
+    /// Cell values keyed by `row.column`: a 0-based data row index
+    /// and a column name (or 0-based column index), e.g. `0.name`.
+    #[serde(default)]
+    pub cell: HashMap<String, String>,
+}
+
+/// Upper bounds for iteration latency percentiles of a looped
+/// step, in milliseconds (nearest-rank method).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoopTimingExpectation {
+    /// Maximum median iteration duration.
+    #[serde(default)]
+    pub p50_ms: Option<u64>,
+    /// Maximum 90th percentile iteration duration.
+    #[serde(default)]
+    pub p90_ms: Option<u64>,
+    /// Maximum 95th percentile iteration duration.
+    #[serde(default)]
+    pub p95_ms: Option<u64>,
+    /// Maximum 99th percentile iteration duration.
+    #[serde(default)]
+    pub p99_ms: Option<u64>,
+}
+
+/// Combination semantics for a step's expectation groups.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// Every declared group must pass (default).
+    #[default]
+    All,
+    /// At least one declared group must pass.
+    Any,
 }
 
 /// Expectations for SSE event streams
@@ -204,8 +667,10 @@ pub struct SseEventExpectation {
     #[serde(default)]
     pub data_eq: Option<serde_json::Value>,
     /// Fields to exclude from `data_eq` comparison.
-    /// Supports dot-separated paths (e.g. "args.stamp_id")
-    /// and wildcard `*` for array elements (e.g. "items.*.id").
+    /// Supports dot-separated paths (e.g. "args.stamp_id"),
+    /// wildcard `*` for a single segment (e.g. "items.*.id"),
+    /// `**` for any number of segments (e.g. "meta.**"), and
+    /// index ranges (e.g. "items[0:2].id", end-exclusive).
     #[serde(default)]
     pub ignore_fields: Vec<String>,
     /// Substring match against the raw data text
@@ -225,7 +690,10 @@ pub struct TestConfig {
     /// TODO: add English documentation
     #[serde(default)]
     pub base_url: Option<String>,
-    /// TODO: add English documentation
+    /// Headers sent with every request unless the step sets the
+    /// same header. `{{ var }}` placeholders are expanded at
+    /// request time, so values saved or bound by earlier steps
+    /// (e.g. `Authorization: "Bearer {{ token }}"`) are picked up.
     #[serde(default)]
     pub headers: HashMap<String, String>,
     /// TODO: add English documentation
@@ -234,6 +702,91 @@ pub struct TestConfig {
     /// TODO: add English documentation
     #[serde(default)]
     pub continue_on_failure: bool,
+    /// Compare JSON numbers by value rather than representation,
+    /// so `1` and `1.0` are treated as equal in `expect.json` and
+    /// `json_eq`.
+    #[serde(default)]
+    pub numeric_loose: bool,
+    /// Treat a numeric string and a number as equal when they hold
+    /// the same value (`"123"` vs `123`) in `expect.json` and
+    /// `json_eq`.
+    #[serde(default)]
+    pub coerce_numeric_strings: bool,
+    /// Header name under which a fresh UUID is sent with every
+    /// request (off by default). The value is also saved as the
+    /// `request_id` variable. A header set explicitly by the step
+    /// or `config.headers` wins.
+    #[serde(default)]
+    pub inject_request_id: Option<String>,
+    /// `Accept` header value (e.g. `application/json`) sent with
+    /// every request that does not set `Accept` itself, either in
+    /// the step or in `headers`. Off by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_accept: Option<String>,
+    /// Path prefix (e.g. `/v2`) prepended to relative request URLs
+    /// before they are joined with `base_url`. Absolute URLs are
+    /// unaffected.
+    #[serde(default)]
+    pub base_path: Option<String>,
+    /// Authorization applied to every request that does not set
+    /// an `Authorization` header itself. Credentials are expanded
+    /// at request time like `headers`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthConfig>,
+    /// Accept invalid or self-signed TLS certificates (e.g. for a
+    /// staging server). Never enable this against production.
+    #[serde(default)]
+    pub insecure_tls: bool,
+    /// Fail the scenario when a `teardown` step fails.
+    #[serde(default)]
+    pub fail_on_teardown: bool,
+    /// Directory of the file the scenario was loaded from, set by
+    /// [`crate::config::TestConfigManager`]. Relative `body_file`
+    /// paths resolve against it, or against the working directory
+    /// when unset.
+    #[serde(skip)]
+    pub base_dir: Option<PathBuf>,
+}
+
+/// Scenario-level request authorization.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AuthConfig {
+    /// `Authorization: Bearer <token>`.
+    Bearer { token: String },
+    /// `Authorization: Basic base64(username:password)`.
+    Basic { username: String, password: String },
+}
+
+impl AuthConfig {
+    /// Copy with the secret replaced by [`crate::redact::REDACTED`].
+    pub fn redacted(&self) -> Self {
+        let secret = crate::redact::REDACTED.to_string();
+        match self {
+            AuthConfig::Bearer { .. } => {
+                AuthConfig::Bearer { token: secret }
+            }
+            AuthConfig::Basic { username, .. } => AuthConfig::Basic {
+                username: username.clone(),
+                password: secret,
+            },
+        }
+    }
+}
+
+impl std::fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.redacted() {
+            AuthConfig::Bearer { token } => {
+                f.debug_struct("Bearer").field("token", &token).finish()
+            }
+            AuthConfig::Basic { username, password } => f
+                .debug_struct("Basic")
+                .field("username", &username)
+                .field("password", &password)
+                .finish(),
+        }
+    }
 }
 
 /// TODO: add English documentation
@@ -246,6 +799,10 @@ fn default_timeout() -> u64 {
     30
 }
 
+fn default_attempts() -> u32 {
+    1
+}
+
 impl TestScenario {
     /// Deserialize a scenario from a YAML string.
     pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
@@ -283,6 +840,14 @@ pub struct TestResult {
     pub error: Option<String>,
     pub steps: Vec<StepResult>,
     pub duration_ms: u64,
+    /// Variables the scenario ended with (saved, bound, and
+    /// scenario vars), excluding internal runner state such as
+    /// `steps`, `current`, `previous`, and `env.*`.
+    ///
+    /// Not serialized, so saved tokens never end up in report
+    /// files or submitted reports.
+    #[serde(default, skip_serializing)]
+    pub outputs: HashMap<String, serde_json::Value>,
 }
 
 /// Result of running a single step within a scenario.
@@ -294,6 +859,118 @@ pub struct StepResult {
     pub request: RequestInfo,
     pub response: Option<ResponseInfo>,
     pub duration_ms: u64,
+    /// Redacted user variables after the step ran. Captured for
+    /// every step when the runner dumps vars, or only for the
+    /// first failing step of a scenario when it dumps them on
+    /// failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vars_snapshot: Option<HashMap<String, serde_json::Value>>,
+    /// `true` when the step's `condition` was not met and no
+    /// request was sent. Skipped steps count as successful.
+    #[serde(default)]
+    pub skipped: bool,
+    /// The unmet condition, for skipped steps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
+    /// Outcome of every individual expectation, recorded only
+    /// when the runner explains steps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<AssertionResult>,
+    /// Every difference found by failed `json_eq` and
+    /// `json_decoded` comparisons, for rendering a readable diff.
+    /// `error` keeps the same mismatches as one message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diff: Vec<DataMismatch>,
+    /// Number of iterations a `loop_config` step ran before it
+    /// stopped; `1` for steps without a loop.
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+}
+
+/// One difference between an expected and an actual JSON value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataMismatch {
+    /// Dot-separated path of the difference (array indices are
+    /// path segments).
+    pub path: String,
+    pub kind: MismatchKind,
+    /// Expected value; the expected array length for
+    /// [`MismatchKind::Length`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<serde_json::Value>,
+    /// Actual value; the actual array length for
+    /// [`MismatchKind::Length`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<serde_json::Value>,
+}
+
+/// What kind of difference a [`DataMismatch`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    /// Both sides have a value, and they differ.
+    Value,
+    /// The expected field is absent from the actual value.
+    Missing,
+    /// The actual value has a field that was not expected.
+    Unexpected,
+    /// The arrays have different lengths.
+    Length,
+}
+
+impl DataMismatch {
+    /// The message `validate_data_eq` reports for this difference.
+    pub fn message(&self) -> String {
+        let show = |v: &Option<serde_json::Value>| match v {
+            Some(v) => format!("{v:?}"),
+            None => "none".to_string(),
+        };
+        let show_len = |v: &Option<serde_json::Value>| match v {
+            Some(v) => v.to_string(),
+            None => "0".to_string(),
+        };
+        let path = &self.path;
+        match self.kind {
+            MismatchKind::Value => format!(
+                "data_eq '{path}': 値が期待値と一致しません。\
+                 期待: {}, 実際: {}",
+                show(&self.expected),
+                show(&self.actual)
+            ),
+            MismatchKind::Missing => format!(
+                "data_eq '{path}': フィールドが存在しません (期待: {})",
+                show(&self.expected)
+            ),
+            MismatchKind::Unexpected => format!(
+                "data_eq '{path}': 予期しないフィールドがあります (値: {})",
+                show(&self.actual)
+            ),
+            MismatchKind::Length => format!(
+                "data_eq '{path}': 配列長が一致しません。\
+                 期待: {}, 実際: {}",
+                show_len(&self.expected),
+                show_len(&self.actual)
+            ),
+        }
+    }
+}
+
+/// Outcome of one expectation of a step, for `--explain`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionResult {
+    /// What was checked, e.g. `status`, `header content-type`,
+    /// or `json data.id`.
+    pub check: String,
+    pub passed: bool,
+    /// Expected value, when the check compares values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Actual value; `None` when absent or not applicable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+    /// Failure message for failed checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Captured HTTP request information for a step.
@@ -302,6 +979,9 @@ pub struct RequestInfo {
     pub method: String,
     pub url: String,
     pub headers: HashMap<String, String>,
+    /// Query parameters after variable expansion.
+    #[serde(default)]
+    pub query: HashMap<String, String>,
     pub body: Option<String>,
 }
 
@@ -309,8 +989,24 @@ pub struct RequestInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseInfo {
     pub status: u16,
+    /// Response headers; repeated headers are joined with `", "`.
     pub headers: HashMap<String, String>,
     pub body: Option<String>,
+    /// Negotiated HTTP version, e.g. `HTTP/1.1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<String>,
+    /// Peer certificate details, for HTTPS responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<CertificateInfo>,
+}
+
+/// Details of a server's TLS certificate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// Expiry as an RFC 3339 timestamp.
+    pub not_after: String,
 }
 
 /// Full report payload sent to Tachyon Ops API.
@@ -320,6 +1016,23 @@ pub struct TestRunReport {
     pub total_duration_ms: u64,
     pub timestamp: String,
     pub ci: Option<CiMetadata>,
+    /// Step latency distribution across the whole run, when
+    /// requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyStats>,
+}
+
+/// Step latency statistics across a run, in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// Number of executed (non-skipped) steps measured.
+    pub count: usize,
+    pub min_ms: u64,
+    pub mean_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
 }
 
 /// CI environment metadata attached to a test run report.
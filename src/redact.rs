@@ -0,0 +1,159 @@
+//! Redaction of secrets in logged or reported request/response
+//! data.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Placeholder substituted for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Header names whose values are always redacted
+/// (compared case-insensitively).
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-auth-token",
+];
+
+/// Substrings that mark a JSON field or variable name as
+/// sensitive (compared case-insensitively).
+const SENSITIVE_KEY_PARTS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "credential",
+];
+
+/// Return `true` if a header with this name carries credentials.
+pub fn is_sensitive_header(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    SENSITIVE_HEADERS.contains(&lower.as_str())
+}
+
+/// Return `true` if a JSON field or variable with this name is
+/// likely to hold a secret.
+pub fn is_sensitive_key(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    SENSITIVE_KEY_PARTS.iter().any(|part| lower.contains(part))
+}
+
+/// Return a copy of `headers` with credential-bearing values
+/// replaced by [`REDACTED`].
+pub fn redact_headers(
+    headers: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    headers
+        .iter()
+        .map(|(k, v)| {
+            if is_sensitive_header(k) {
+                (k.clone(), REDACTED.to_string())
+            } else {
+                (k.clone(), v.clone())
+            }
+        })
+        .collect()
+}
+
+/// Recursively replace the values of sensitive object keys in a
+/// JSON tree with [`REDACTED`].
+pub fn redact_value(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    if is_sensitive_key(k) {
+                        (k.clone(), Value::String(REDACTED.to_string()))
+                    } else {
+                        (k.clone(), redact_value(v))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(arr) => {
+            Value::Array(arr.iter().map(redact_value).collect())
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Return a copy of a variable map with sensitive names replaced
+/// by [`REDACTED`] and sensitive fields redacted inside values.
+pub fn redact_vars(
+    vars: &HashMap<String, Value>,
+) -> HashMap<String, Value> {
+    vars.iter()
+        .map(|(k, v)| {
+            if is_sensitive_key(k) {
+                (k.clone(), Value::String(REDACTED.to_string()))
+            } else {
+                (k.clone(), redact_value(v))
+            }
+        })
+        .collect()
+}
+
+/// Redact sensitive fields in a body string.
+///
+/// JSON bodies are parsed and redacted with [`redact_value`];
+/// anything else is returned unchanged.
+pub fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(json) => redact_value(&json).to_string(),
+        Err(_) => body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_headers() {
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer x".to_string());
+        headers
+            .insert("Content-Type".to_string(), "text/plain".to_string());
+
+        let redacted = redact_headers(&headers);
+        assert_eq!(redacted["Authorization"], REDACTED);
+        assert_eq!(redacted["Content-Type"], "text/plain");
+    }
+
+    #[test]
+    fn test_redact_value_nested() {
+        let value = json!({
+            "user": {"name": "alice", "password": "hunter2"},
+            "items": [{"access_token": "abc"}]
+        });
+        let redacted = redact_value(&value);
+        assert_eq!(redacted["user"]["name"], json!("alice"));
+        assert_eq!(redacted["user"]["password"], json!(REDACTED));
+        assert_eq!(redacted["items"][0]["access_token"], json!(REDACTED));
+    }
+
+    #[test]
+    fn test_redact_vars() {
+        let mut vars = HashMap::new();
+        vars.insert("auth_token".to_string(), json!("abc"));
+        vars.insert("user".to_string(), json!({"password": "x", "id": 1}));
+
+        let redacted = redact_vars(&vars);
+        assert_eq!(redacted["auth_token"], json!(REDACTED));
+        assert_eq!(redacted["user"]["password"], json!(REDACTED));
+        assert_eq!(redacted["user"]["id"], json!(1));
+    }
+
+    #[test]
+    fn test_redact_body_non_json_unchanged() {
+        assert_eq!(redact_body("plain text"), "plain text");
+        assert!(redact_body(r#"{"token":"abc"}"#).contains(REDACTED));
+    }
+}
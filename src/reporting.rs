@@ -0,0 +1,680 @@
+//! Report formats for CI tooling.
+
+use crate::model::{
+    DataMismatch, MismatchKind, RequestInfo, ResponseInfo, StepResult,
+    TestResult, TestRunReport,
+};
+use crate::redact;
+use std::collections::{BTreeMap, HashMap};
+
+/// Render a scenario result as a JUnit XML `<testsuite>`.
+///
+/// The scenario becomes the test suite and each step a test case;
+/// step errors become `<failure>` elements and skipped steps
+/// `<skipped>`. Times are in seconds. A scenario that failed
+/// without a failing step (e.g. a scenario-level `assert`) gets an
+/// extra test case carrying the scenario error.
+pub fn junit_xml(result: &TestResult) -> String {
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    push_testsuite(&mut xml, result);
+    xml
+}
+
+/// Render a whole run as a JUnit XML `<testsuites>` document with
+/// the overall totals, one `<testsuite>` per scenario as in
+/// [`junit_xml`].
+pub fn junit_xml_run(report: &TestRunReport) -> String {
+    let totals = RunTotals::of(report);
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"muon\" tests=\"{}\" failures=\"{}\" \
+         skipped=\"{}\" time=\"{}\">\n",
+        totals.steps,
+        totals.failed_steps,
+        totals.skipped_steps,
+        seconds(report.total_duration_ms)
+    ));
+    for result in &report.scenarios {
+        push_testsuite(&mut xml, result);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn push_testsuite(xml: &mut String, result: &TestResult) {
+    let scenario_failure = (!result.success
+        && result.steps.iter().all(|s| s.success))
+    .then(|| result.error.clone().unwrap_or_default());
+
+    let tests =
+        result.steps.len() + usize::from(scenario_failure.is_some());
+    let failures = result.steps.iter().filter(|s| !s.success).count()
+        + usize::from(scenario_failure.is_some());
+    let skipped = result.steps.iter().filter(|s| s.skipped).count();
+
+    xml.push_str(&format!(
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" \
+         skipped=\"{}\" time=\"{}\">\n",
+        xml_escape(&result.name),
+        tests,
+        failures,
+        skipped,
+        seconds(result.duration_ms)
+    ));
+    for step in &result.steps {
+        push_testcase(xml, &result.name, step);
+    }
+    if let Some(error) = scenario_failure {
+        let name = xml_escape(&result.name);
+        let error = xml_escape(&error);
+        xml.push_str(&format!(
+            "  <testcase name=\"{name}\" classname=\"{name}\" \
+             time=\"0.000\">\n"
+        ));
+        xml.push_str(&format!(
+            "    <failure message=\"{error}\">{error}</failure>\n"
+        ));
+        xml.push_str("  </testcase>\n");
+    }
+    xml.push_str("</testsuite>\n");
+}
+
+fn push_testcase(xml: &mut String, suite: &str, step: &StepResult) {
+    let open = format!(
+        "  <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+        xml_escape(&step.name),
+        xml_escape(suite),
+        seconds(step.duration_ms)
+    );
+    if step.skipped {
+        let reason = step.skip_reason.as_deref().unwrap_or_default();
+        xml.push_str(&format!(
+            "{open}>\n    <skipped message=\"{}\"/>\n  </testcase>\n",
+            xml_escape(reason)
+        ));
+    } else if !step.success {
+        let error = xml_escape(step.error.as_deref().unwrap_or("failed"));
+        xml.push_str(&format!(
+            "{open}>\n    <failure message=\"{error}\">{error}</failure>\n  \
+             </testcase>\n"
+        ));
+    } else {
+        xml.push_str(&format!("{open}/>\n"));
+    }
+}
+
+/// Milliseconds as a JUnit `time` value in seconds.
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// Render a scenario result as a TAP version 13 document.
+///
+/// Each step is one test point, followed by the scenario summary
+/// as a comment; see [`tap_tests`].
+pub fn tap(result: &TestResult) -> String {
+    let (tests, count) = tap_tests(result, 1);
+    format!("TAP version 13\n1..{count}\n{tests}")
+}
+
+/// Render the TAP test points of a scenario, numbered from
+/// `first`, and return them with the number of points.
+///
+/// Step names are the descriptions, skipped steps get a `# SKIP`
+/// directive, and failures carry a YAML diagnostic block with the
+/// error. Like [`junit_xml`], a scenario that failed without a
+/// failing step gets an extra point carrying the scenario error.
+/// Streaming several scenarios into one document only needs the
+/// version line first and a `1..N` plan at the end.
+pub fn tap_tests(result: &TestResult, first: usize) -> (String, usize) {
+    let mut out = String::new();
+    let mut number = first;
+    for step in &result.steps {
+        let name = tap_description(&step.name);
+        if step.skipped {
+            let reason = step.skip_reason.as_deref().unwrap_or_default();
+            out.push_str(&format!(
+                "ok {number} - {name} # SKIP {}\n",
+                tap_description(reason)
+            ));
+        } else if step.success {
+            out.push_str(&format!("ok {number} - {name}\n"));
+        } else {
+            out.push_str(&format!("not ok {number} - {name}\n"));
+            push_tap_diagnostic(
+                &mut out,
+                step.error.as_deref().unwrap_or("failed"),
+                step.duration_ms,
+            );
+        }
+        number += 1;
+    }
+    if !result.success && result.steps.iter().all(|s| s.success) {
+        out.push_str(&format!(
+            "not ok {number} - {}\n",
+            tap_description(&result.name)
+        ));
+        push_tap_diagnostic(
+            &mut out,
+            result.error.as_deref().unwrap_or("failed"),
+            result.duration_ms,
+        );
+        number += 1;
+    }
+    out.push_str(&format!(
+        "# {} {} ({} ms)\n",
+        if result.success { "PASS" } else { "FAIL" },
+        result.name,
+        result.duration_ms
+    ));
+    (out, number - first)
+}
+
+/// Render a whole run as one TAP version 13 document, numbering
+/// the test points across scenarios and ending with the overall
+/// totals as a comment.
+pub fn tap_run(report: &TestRunReport) -> String {
+    let mut tests = String::new();
+    let mut count = 0;
+    for result in &report.scenarios {
+        let (scenario_tests, n) = tap_tests(result, count + 1);
+        tests.push_str(&scenario_tests);
+        count += n;
+    }
+    format!(
+        "TAP version 13\n1..{count}\n{tests}# {}\n",
+        RunTotals::of(report).summary(report.total_duration_ms)
+    )
+}
+
+/// Append a TAP 13 YAML diagnostic block.
+fn push_tap_diagnostic(out: &mut String, error: &str, duration_ms: u64) {
+    out.push_str("  ---\n");
+    out.push_str(&format!(
+        "  message: {}\n",
+        serde_json::to_string(error).unwrap_or_default()
+    ));
+    out.push_str(&format!("  duration_ms: {duration_ms}\n"));
+    out.push_str("  ...\n");
+}
+
+/// A TAP description on one line, with `#` escaped so it is not
+/// read as a directive.
+fn tap_description(text: &str) -> String {
+    text.replace(['\r', '\n'], " ").replace('#', "\\#")
+}
+
+/// Inline stylesheet of [`html`] reports.
+const HTML_STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:2rem;color:#222}\
+h1{font-size:1.4rem}\
+.badge{display:inline-block;padding:.1rem .5rem;border-radius:.3rem;\
+color:#fff;font-size:.8rem;font-weight:bold}\
+.pass{background:#2e7d32}.fail{background:#c62828}.skip{background:#9e9e9e}\
+details{border:1px solid #ddd;border-radius:.3rem;margin:.4rem 0;\
+padding:.4rem .8rem}\
+summary{cursor:pointer}\
+.error{color:#c62828;white-space:pre-wrap}\
+.muted{color:#666}\
+pre{background:#f6f8fa;padding:.6rem;overflow-x:auto}";
+
+/// Render a scenario result as a self-contained HTML page.
+///
+/// The header shows the scenario status, duration and error; each
+/// step is a collapsible section with its status badge, duration,
+/// error, and the (redacted) request and response. Styles are
+/// inline, so the file can be attached or opened anywhere.
+pub fn html(result: &TestResult) -> String {
+    let mut out = html_head(&result.name);
+    push_html_scenario(&mut out, result, "h1");
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Render a whole run as one HTML page: the overall totals, then
+/// a section per scenario laid out as in [`html`].
+pub fn html_run(report: &TestRunReport) -> String {
+    let mut out = html_head("muon test run");
+    out.push_str(&format!(
+        "<h1>{} muon test run</h1>\n<p class=\"muted\">{}</p>\n",
+        html_badge(report.scenarios.iter().all(|r| r.success), false),
+        xml_escape(
+            &RunTotals::of(report).summary(report.total_duration_ms)
+        )
+    ));
+    for result in &report.scenarios {
+        push_html_scenario(&mut out, result, "h2");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Document start through the opening `<body>` tag.
+fn html_head(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n\
+         <meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>{HTML_STYLE}</style>\n</head>\n<body>\n",
+        xml_escape(title)
+    )
+}
+
+fn push_html_scenario(
+    out: &mut String,
+    result: &TestResult,
+    heading: &str,
+) {
+    let name = xml_escape(&result.name);
+    let passed = result.steps.iter().filter(|s| s.success).count();
+    out.push_str(&format!(
+        "<{heading}>{} {name}</{heading}>\n\
+         <p class=\"muted\">{passed}/{} steps passed in {} ms</p>\n",
+        html_badge(result.success, false),
+        result.steps.len(),
+        result.duration_ms
+    ));
+    if let Some(error) = &result.error {
+        out.push_str(&format!(
+            "<p class=\"error\">{}</p>\n",
+            xml_escape(error)
+        ));
+    }
+    for (i, step) in result.steps.iter().enumerate() {
+        push_html_step(out, i + 1, step);
+    }
+}
+
+fn push_html_step(out: &mut String, number: usize, step: &StepResult) {
+    // Failed steps start expanded
+    let open = if step.success { "" } else { " open" };
+    out.push_str(&format!(
+        "<details{open}>\n<summary>{} {number}. {} \
+         <span class=\"muted\">({} ms)</span></summary>\n",
+        html_badge(step.success, step.skipped),
+        xml_escape(&step.name),
+        step.duration_ms
+    ));
+    if let Some(reason) = &step.skip_reason {
+        out.push_str(&format!(
+            "<p class=\"muted\">Skipped: {}</p>\n",
+            xml_escape(reason)
+        ));
+    }
+    if let Some(error) = &step.error {
+        out.push_str(&format!(
+            "<p class=\"error\">{}</p>\n",
+            xml_escape(error)
+        ));
+    }
+    push_html_request(out, &step.request);
+    if let Some(response) = &step.response {
+        push_html_response(out, response);
+    }
+    out.push_str("</details>\n");
+}
+
+fn push_html_request(out: &mut String, request: &RequestInfo) {
+    let mut text = format!("{} {}\n", request.method, request.url);
+    let query: BTreeMap<_, _> = request.query.iter().collect();
+    for (name, value) in query {
+        text.push_str(&format!("?{name}={value}\n"));
+    }
+    push_header_lines(&mut text, &request.headers);
+    if let Some(body) = &request.body {
+        text.push_str(&format!("\n{}\n", redact::redact_body(body)));
+    }
+    out.push_str(&format!(
+        "<h4>Request</h4>\n<pre>{}</pre>\n",
+        xml_escape(&text)
+    ));
+}
+
+fn push_html_response(out: &mut String, response: &ResponseInfo) {
+    let mut text = format!("Status {}\n", response.status);
+    push_header_lines(&mut text, &response.headers);
+    if let Some(body) = &response.body {
+        text.push_str(&format!("\n{}\n", redact::redact_body(body)));
+    }
+    out.push_str(&format!(
+        "<h4>Response</h4>\n<pre>{}</pre>\n",
+        xml_escape(&text)
+    ));
+}
+
+/// Append `name: value` lines, sorted and with secrets redacted.
+fn push_header_lines(text: &mut String, headers: &HashMap<String, String>) {
+    let headers: BTreeMap<_, _> =
+        redact::redact_headers(headers).into_iter().collect();
+    for (name, value) in headers {
+        text.push_str(&format!("{name}: {value}\n"));
+    }
+}
+
+fn html_badge(success: bool, skipped: bool) -> &'static str {
+    if skipped {
+        "<span class=\"badge skip\">SKIP</span>"
+    } else if success {
+        "<span class=\"badge pass\">PASS</span>"
+    } else {
+        "<span class=\"badge fail\">FAIL</span>"
+    }
+}
+
+/// Render `json_eq` mismatches as an expected/actual diff, one
+/// path per entry with `-` for the expected and `+` for the
+/// actual side. `color` adds red/green ANSI colors.
+pub fn format_diff(diff: &[DataMismatch], color: bool) -> String {
+    let paint = |code: &str, text: String| {
+        if color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text
+        }
+    };
+    let show = |value: &Option<serde_json::Value>| {
+        value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+    };
+    let mut out = String::new();
+    for mismatch in diff {
+        let path = if mismatch.path.is_empty() {
+            "(root)"
+        } else {
+            &mismatch.path
+        };
+        let (note, expected, actual) = match mismatch.kind {
+            MismatchKind::Value => ("", true, true),
+            MismatchKind::Missing => (" (missing)", true, false),
+            MismatchKind::Unexpected => (" (unexpected)", false, true),
+            MismatchKind::Length => (" (array length)", true, true),
+        };
+        out.push_str(&format!("{path}{note}\n"));
+        if expected {
+            out.push_str(&paint(
+                "31",
+                format!("  - expected: {}", show(&mismatch.expected)),
+            ));
+            out.push('\n');
+        }
+        if actual {
+            out.push_str(&paint(
+                "32",
+                format!("  + actual:   {}", show(&mismatch.actual)),
+            ));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Scenario and step counts across a run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RunTotals {
+    pub scenarios: usize,
+    pub passed_scenarios: usize,
+    pub steps: usize,
+    pub failed_steps: usize,
+    pub skipped_steps: usize,
+}
+
+impl RunTotals {
+    /// Count the scenarios and steps of `report`.
+    pub fn of(report: &TestRunReport) -> Self {
+        let steps = report.scenarios.iter().flat_map(|r| &r.steps);
+        Self {
+            scenarios: report.scenarios.len(),
+            passed_scenarios: report
+                .scenarios
+                .iter()
+                .filter(|r| r.success)
+                .count(),
+            steps: steps.clone().count(),
+            failed_steps: steps.clone().filter(|s| !s.success).count(),
+            skipped_steps: steps.filter(|s| s.skipped).count(),
+        }
+    }
+
+    /// One-line summary, e.g. `2/3 scenarios passed, 1 of 7
+    /// steps failed, 0 skipped (120 ms)`.
+    pub fn summary(&self, duration_ms: u64) -> String {
+        format!(
+            "{}/{} scenarios passed, {} of {} steps failed, {} skipped \
+             ({duration_ms} ms)",
+            self.passed_scenarios,
+            self.scenarios,
+            self.failed_steps,
+            self.steps,
+            self.skipped_steps
+        )
+    }
+}
+
+/// Escape text for use in XML attributes and element content.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab/newline are not
+            // allowed in XML 1.0
+            c if c.is_control() && c != '\t' && c != '\n' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::RequestInfo;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn step(
+        name: &str,
+        error: Option<&str>,
+        duration_ms: u64,
+    ) -> StepResult {
+        StepResult {
+            name: name.to_string(),
+            success: error.is_none(),
+            error: error.map(str::to_string),
+            request: RequestInfo {
+                method: "GET".to_string(),
+                url: "/".to_string(),
+                headers: HashMap::new(),
+                query: HashMap::new(),
+                body: None,
+            },
+            response: None,
+            duration_ms,
+            vars_snapshot: None,
+            skipped: false,
+            skip_reason: None,
+            assertions: Vec::new(),
+            diff: Vec::new(),
+            attempts: 1,
+        }
+    }
+
+    fn result(steps: Vec<StepResult>) -> TestResult {
+        TestResult {
+            name: "Users <CRUD>".to_string(),
+            success: steps.iter().all(|s| s.success),
+            error: None,
+            steps,
+            duration_ms: 1500,
+            outputs: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_junit_maps_steps_to_testcases() {
+        let xml = junit_xml(&result(vec![
+            step("create", None, 250),
+            step("fetch", Some("expected 200, got \"404\" & <html>"), 10),
+        ]));
+
+        assert!(xml.contains(
+            "<testsuite name=\"Users &lt;CRUD&gt;\" tests=\"2\" \
+             failures=\"1\" skipped=\"0\" time=\"1.500\">"
+        ));
+        assert!(xml.contains(
+            "<testcase name=\"create\" classname=\"Users &lt;CRUD&gt;\" \
+             time=\"0.250\"/>"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"expected 200, got &quot;404&quot; \
+             &amp; &lt;html&gt;\">"
+        ));
+    }
+
+    #[test]
+    fn test_junit_marks_skipped_steps() {
+        let mut skipped = step("optional", None, 0);
+        skipped.skipped = true;
+        skipped.skip_reason = Some("condition not met: x".to_string());
+        let xml = junit_xml(&result(vec![skipped]));
+
+        assert!(xml.contains("skipped=\"1\""));
+        assert!(xml.contains("<skipped message=\"condition not met: x\"/>"));
+    }
+
+    #[test]
+    fn test_tap_document() {
+        let mut skipped = step("optional", None, 0);
+        skipped.skipped = true;
+        skipped.skip_reason = Some("condition not met: x".to_string());
+        let tap = tap(&result(vec![
+            step("create #1", None, 250),
+            step("fetch", Some("expected \"200\"\ngot 404"), 10),
+            skipped,
+        ]));
+
+        assert_eq!(
+            tap,
+            "TAP version 13\n1..3\n\
+             ok 1 - create \\#1\n\
+             not ok 2 - fetch\n  ---\n  \
+             message: \"expected \\\"200\\\"\\ngot 404\"\n  \
+             duration_ms: 10\n  ...\n\
+             ok 3 - optional # SKIP condition not met: x\n\
+             # FAIL Users <CRUD> (1500 ms)\n"
+        );
+    }
+
+    #[test]
+    fn test_tap_numbers_from_first_and_reports_scenario_failure() {
+        let mut failed = result(vec![step("create", None, 5)]);
+        failed.success = false;
+        failed.error = Some("scenario assert failed".to_string());
+        let (tests, count) = tap_tests(&failed, 4);
+
+        assert_eq!(count, 2);
+        assert!(
+            tests.starts_with("ok 4 - create\nnot ok 5 - Users <CRUD>\n")
+        );
+        assert!(tests.contains("message: \"scenario assert failed\""));
+    }
+
+    #[test]
+    fn test_html_escapes_and_redacts() {
+        let mut failed =
+            step("fetch <user>", Some("expected 200 & got 404"), 10);
+        failed.request.headers.insert(
+            "Authorization".to_string(),
+            "Bearer s3cret".to_string(),
+        );
+        let mut report = result(vec![step("create", None, 250), failed]);
+        report.success = false;
+        let html = html(&report);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Users &lt;CRUD&gt;</title>"));
+        assert!(html.contains("1/2 steps passed in 1500 ms"));
+        assert!(html.contains("<details open>"));
+        assert!(html.contains("fetch &lt;user&gt;"));
+        assert!(html.contains("expected 200 &amp; got 404"));
+        assert!(html.contains("Authorization: [REDACTED]"));
+        assert!(!html.contains("s3cret"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+    }
+
+    fn run(scenarios: Vec<TestResult>) -> TestRunReport {
+        TestRunReport {
+            scenarios,
+            total_duration_ms: 2000,
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            ci: None,
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_run_reports_include_totals() {
+        let report = run(vec![
+            result(vec![step("create", None, 5)]),
+            result(vec![
+                step("fetch", Some("boom"), 5),
+                step("x", None, 1),
+            ]),
+        ]);
+
+        let xml = junit_xml_run(&report);
+        assert!(xml.contains(
+            "<testsuites name=\"muon\" tests=\"3\" failures=\"1\" \
+             skipped=\"0\" time=\"2.000\">"
+        ));
+        assert_eq!(xml.matches("<testsuite ").count(), 2);
+
+        let tap = tap_run(&report);
+        assert!(tap.starts_with("TAP version 13\n1..3\nok 1 - create\n"));
+        assert!(tap.contains("not ok 2 - fetch\n"));
+        assert!(tap.ends_with(
+            "# 1/2 scenarios passed, 1 of 3 steps failed, 0 skipped \
+             (2000 ms)\n"
+        ));
+
+        let html = html_run(&report);
+        assert_eq!(html.matches("<h2>").count(), 2);
+        assert!(html.contains("1/2 scenarios passed"));
+    }
+
+    #[test]
+    fn test_format_diff_lists_each_path() {
+        let diff = crate::validator::diff_data_eq(
+            &json!({"name": "bob", "extra": 1, "items": [1]}),
+            &json!({"name": "alice", "id": 5, "items": [1, 2]}),
+            &[],
+            "",
+            Default::default(),
+        );
+        assert_eq!(
+            format_diff(&diff, false),
+            "extra (unexpected)\n  + actual:   1\n\
+             id (missing)\n  - expected: 5\n\
+             items (array length)\n  - expected: 2\n  + actual:   1\n\
+             name\n  - expected: \"alice\"\n  + actual:   \"bob\"\n"
+        );
+        assert!(
+            format_diff(&diff, true).contains("\x1b[31m  - expected: 5")
+        );
+    }
+
+    #[test]
+    fn test_junit_reports_scenario_level_failure() {
+        let mut failed = result(vec![step("create", None, 5)]);
+        failed.success = false;
+        failed.error = Some("scenario assert failed: a < b".to_string());
+        let xml = junit_xml(&failed);
+
+        assert!(xml.contains("tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("scenario assert failed: a &lt; b"));
+    }
+}
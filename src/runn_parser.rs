@@ -80,6 +80,7 @@ pub fn parse_runbook(yaml: &str) -> Result<TestScenario> {
         headers: HashMap::new(),
         timeout: 30,
         continue_on_failure: runbook.force,
+        ..Default::default()
     };
 
     Ok(TestScenario {
@@ -89,6 +90,12 @@ pub fn parse_runbook(yaml: &str) -> Result<TestScenario> {
         steps,
         vars: runbook.vars,
         config,
+        requires_env: vec![],
+        assert: None,
+        weight: None,
+        environments: HashMap::new(),
+        setup: Vec::new(),
+        teardown: Vec::new(),
     })
 }
 
@@ -228,22 +235,20 @@ fn convert_single_step(
         description: desc,
         request,
         expect: ResponseExpectation {
-            status: expected_status,
-            headers: HashMap::new(),
-            json: HashMap::new(),
-            json_lengths: HashMap::new(),
-            schema: None,
-            contains: vec![],
-            json_eq: None,
-            json_ignore_fields: vec![],
-            sse: None,
+            status: Some(expected_status.into()),
+            ..Default::default()
         },
         save: HashMap::new(),
+        save_regex: HashMap::new(),
         condition: None,
         test,
         bind,
         loop_config,
+        retry: None,
         include: None,
+        inject_request_id: None,
+        timeout: None,
+        vars: HashMap::new(),
     }))
 }
 
@@ -348,6 +353,9 @@ fn parse_runn_request(
         headers,
         query: HashMap::new(),
         body,
+        body_file: None,
+        compress: None,
+        body_type: BodyType::Json,
     })
 }
 
@@ -462,7 +470,7 @@ steps:
         assert!(matches!(step1.request.method, HttpMethod::Post));
         assert!(step1.request.body.is_some());
         assert!(step1.test.is_some());
-        assert_eq!(step1.expect.status, 201); // inferred
+        assert_eq!(step1.expect.status, Some(201.into())); // inferred
         assert!(step1.bind.contains_key("user_id"));
 
         // Second step
@@ -5,10 +5,16 @@ use crate::model::*;
 use crate::sse;
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use chrono::Utc;
 use regex::Regex;
-use reqwest::{Client, Method as ReqMethod, Response};
+use reqwest::{
+    multipart, Client, Method as ReqMethod, RequestBuilder, Response,
+};
 use serde_json::{Map, Number, Value};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, instrument, warn};
 
@@ -19,23 +25,222 @@ pub trait TestRunner: Send + Sync {
     async fn run(&self, scenario: &TestScenario) -> Result<TestResult>;
 }
 
+/// Receives progress notifications while a scenario runs.
+pub trait StepObserver: Send + Sync {
+    /// Called after each step, including steps skipped by their
+    /// `condition` (with [`StepResult::skipped`] set). Looped steps
+    /// are reported once, with the result of the final iteration.
+    fn on_step(&self, scenario: &TestScenario, step: &StepResult);
+}
+
+/// Settings for the HTTP client built by [`DefaultTestRunner`].
+///
+/// Without `proxy`, the standard `HTTP_PROXY` / `HTTPS_PROXY` /
+/// `NO_PROXY` environment variables are honored.
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfig {
+    /// Send every request through this proxy URL.
+    pub proxy: Option<String>,
+    /// Accept invalid or self-signed TLS certificates.
+    pub accept_invalid_certs: bool,
+    /// Maximum idle connections kept open per host (reqwest's
+    /// default is unlimited).
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle connection is kept for reuse (reqwest's
+    /// default is 90 seconds).
+    pub pool_idle_timeout: Option<Duration>,
+}
+
+impl ClientConfig {
+    /// Build a client with these settings.
+    pub fn build(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .tls_info(true)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(ref proxy) = self.proxy {
+            builder = builder.proxy(
+                reqwest::Proxy::all(proxy)
+                    .context(format!("Invalid proxy URL: {proxy}"))?,
+            );
+        }
+        builder.build().context("Failed to build HTTP client")
+    }
+}
+
 /// Default test runner
-#[derive(Debug)]
 pub struct DefaultTestRunner {
     client: Client,
+    /// Settings `client` was built from; also the base of
+    /// `insecure_client`.
+    client_config: ClientConfig,
+    /// Client that skips certificate verification, built on first
+    /// use by a scenario with `config.insecure_tls`.
+    insecure_client: OnceLock<Client>,
+    dump_vars_on_failure: bool,
+    dump_vars: bool,
+    explain: bool,
+    dry_run: bool,
+    observer: Option<Arc<dyn StepObserver>>,
+}
+
+impl std::fmt::Debug for DefaultTestRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultTestRunner")
+            .field("client", &self.client)
+            .field("client_config", &self.client_config)
+            .field("dump_vars_on_failure", &self.dump_vars_on_failure)
+            .field("dump_vars", &self.dump_vars)
+            .field("explain", &self.explain)
+            .field("dry_run", &self.dry_run)
+            .field("observer", &self.observer.is_some())
+            .finish()
+    }
 }
 
 impl DefaultTestRunner {
     /// Create a new test runner
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+        Self::with_client_config(ClientConfig::default())
+            .expect("failed to build HTTP client")
+    }
+
+    /// Create a new test runner whose client is built from
+    /// `config`.
+    pub fn with_client_config(config: ClientConfig) -> Result<Self> {
+        let client = config.build()?;
+        Ok(Self {
+            client_config: config,
+            ..Self::with_client(client)
+        })
     }
 
     /// Create a new test runner with a custom client
     pub fn with_client(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            client_config: ClientConfig::default(),
+            insecure_client: OnceLock::new(),
+            dump_vars_on_failure: false,
+            dump_vars: false,
+            explain: false,
+            dry_run: false,
+            observer: None,
+        }
+    }
+
+    /// Send every request through the proxy at `url` instead of
+    /// the one from `HTTP_PROXY` / `HTTPS_PROXY`.
+    ///
+    /// This rebuilds the client from the runner's
+    /// [`ClientConfig`], replacing one passed to
+    /// [`Self::with_client`].
+    pub fn with_proxy(mut self, url: &str) -> Result<Self> {
+        self.client_config.proxy = Some(url.to_string());
+        self.rebuild_client()
+    }
+
+    /// Tune connection reuse: keep at most `max_idle_per_host` idle
+    /// connections per host, each for up to `idle_timeout`.
+    ///
+    /// Every step of every scenario shares one connection pool, so
+    /// steps against the same host reuse open connections. Like
+    /// [`Self::with_proxy`], this rebuilds the client.
+    pub fn with_pool_config(
+        mut self,
+        max_idle_per_host: usize,
+        idle_timeout: Option<Duration>,
+    ) -> Result<Self> {
+        self.client_config.pool_max_idle_per_host = Some(max_idle_per_host);
+        self.client_config.pool_idle_timeout = idle_timeout;
+        self.rebuild_client()
+    }
+
+    /// Replace the clients with ones built from `client_config`.
+    fn rebuild_client(mut self) -> Result<Self> {
+        self.client = self.client_config.build()?;
+        self.insecure_client = OnceLock::new();
+        Ok(self)
+    }
+
+    /// Client for requests under `config`: the shared client, or
+    /// one that accepts invalid certificates for `insecure_tls`.
+    ///
+    /// The insecure client is built from the runner's
+    /// [`ClientConfig`], so options of a client passed to
+    /// [`Self::with_client`] do not carry over to it.
+    fn client_for(&self, config: &TestConfig) -> &Client {
+        if !config.insecure_tls {
+            return &self.client;
+        }
+        self.insecure_client.get_or_init(|| {
+            ClientConfig {
+                accept_invalid_certs: true,
+                ..self.client_config.clone()
+            }
+            .build()
+            .expect("failed to build insecure HTTP client")
+        })
+    }
+
+    /// Notify `observer` as each step completes.
+    pub fn with_observer(
+        mut self,
+        observer: Arc<dyn StepObserver>,
+    ) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Capture a redacted snapshot of the variables in
+    /// [`StepResult::vars_snapshot`] for the first failing step of
+    /// each scenario.
+    pub fn with_dump_vars_on_failure(mut self, enabled: bool) -> Self {
+        self.dump_vars_on_failure = enabled;
+        self
+    }
+
+    /// Capture a redacted snapshot of the variables in
+    /// [`StepResult::vars_snapshot`] after every executed step,
+    /// passing or failing, to trace `save`/`bind` across a
+    /// scenario.
+    pub fn with_dump_vars(mut self, enabled: bool) -> Self {
+        self.dump_vars = enabled;
+        self
+    }
+
+    /// Record every expectation's outcome in
+    /// [`StepResult::assertions`], not just the failure message.
+    pub fn with_explain(mut self, enabled: bool) -> Self {
+        self.explain = enabled;
+        self
+    }
+
+    /// Build every request without sending it.
+    ///
+    /// Each step is reported as skipped with the fully resolved
+    /// request in [`StepResult::request`]; `expect`, `save`, `test`
+    /// and scenario assertions are not evaluated.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Snapshot the user variables after a step: always with
+    /// [`Self::with_dump_vars`], or for a failed step with
+    /// [`Self::with_dump_vars_on_failure`].
+    fn vars_snapshot(
+        &self,
+        success: bool,
+        vars: &HashMap<String, Value>,
+    ) -> Option<HashMap<String, Value>> {
+        (self.dump_vars || (self.dump_vars_on_failure && !success))
+            .then(|| crate::redact::redact_vars(&collect_outputs(vars)))
     }
 
     fn slugify(name: &str) -> String {
@@ -62,12 +267,22 @@ impl DefaultTestRunner {
 
     /// Build a runn-compatible `current` value containing `res`
     /// and `req` sub-objects.
+    ///
+    /// `res.rawBody` always holds the unparsed body string, so
+    /// `test:` expressions can assert on non-JSON responses;
+    /// `res.body` is the parsed JSON, or the raw string when the
+    /// body is not JSON.
+    ///
+    /// `req.query` merges the step's `query` with parameters
+    /// written in the URL; `req.resolvedUrl` is the URL with all of
+    /// them appended, and `req.path` / `req.pathSegments` its path.
     fn build_current_value(
         status: u16,
         headers: &HashMap<String, String>,
         parsed_json: &Option<Value>,
         raw_body: &str,
         req_info: &RequestInfo,
+        elapsed_ms: u64,
     ) -> Value {
         // Build res object (runn-compatible)
         let mut res = Map::new();
@@ -86,6 +301,7 @@ impl DefaultTestRunner {
             res.insert("body".into(), Value::String(raw_body.into()));
         }
         res.insert("rawBody".into(), Value::String(raw_body.into()));
+        res.insert("elapsedMs".into(), Value::from(elapsed_ms));
 
         // Build req object
         let mut req = Map::new();
@@ -97,6 +313,37 @@ impl DefaultTestRunner {
                 Self::map_string_to_value(&req_info.headers),
             );
         }
+        // Query parameters written in the URL itself join `query`
+        // (explicit ones win); `resolvedUrl` carries all of them
+        let mut query = req_info.query.clone();
+        if let Ok(mut url) = url::Url::parse(&req_info.url) {
+            for (name, value) in url.query_pairs() {
+                query
+                    .entry(name.into_owned())
+                    .or_insert_with(|| value.into_owned());
+            }
+            if !req_info.query.is_empty() {
+                let mut extra: Vec<_> = req_info.query.iter().collect();
+                extra.sort();
+                url.query_pairs_mut().extend_pairs(extra);
+            }
+            let segments: Vec<Value> = url
+                .path_segments()
+                .into_iter()
+                .flatten()
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| Value::String(segment.into()))
+                .collect();
+            req.insert(
+                "resolvedUrl".into(),
+                Value::String(url.to_string()),
+            );
+            req.insert("path".into(), Value::String(url.path().into()));
+            req.insert("pathSegments".into(), Value::Array(segments));
+        }
+        if !query.is_empty() {
+            req.insert("query".into(), Self::map_string_to_value(&query));
+        }
         if let Some(body) = &req_info.body {
             if let Ok(parsed) = serde_json::from_str::<Value>(body) {
                 req.insert("body".into(), parsed);
@@ -112,6 +359,14 @@ impl DefaultTestRunner {
     }
 
     /// Execute a single step with optional loop/retry.
+    ///
+    /// While looping, the zero-based iteration index is exposed
+    /// as the `i` variable (runn-compatible) and removed again
+    /// once the loop finishes.
+    ///
+    /// With `expect.loop_timing` and no `until`, every iteration
+    /// runs (instead of stopping at the first success) and the
+    /// iteration latency percentiles are checked afterwards.
     #[allow(clippy::too_many_arguments)]
     async fn execute_step_with_loop(
         &self,
@@ -127,8 +382,12 @@ impl DefaultTestRunner {
             let max = loop_cfg.count;
             let mut interval = loop_cfg.interval;
             let mut last_result: Option<StepResult> = None;
+            let mut finished: Option<Option<StepResult>> = None;
+            let mut durations: Vec<u64> = Vec::new();
+            let mut attempts = 0;
 
             for i in 0..max {
+                vars.insert(LOOP_INDEX_VAR.to_string(), Value::from(i));
                 debug!(
                     "Loop iteration {}/{} for step '{}'",
                     i + 1,
@@ -147,6 +406,19 @@ impl DefaultTestRunner {
                         previous_value,
                     )
                     .await?;
+                attempts = i + 1;
+                if let Some(ref r) = result {
+                    if !r.skipped {
+                        durations.push(r.duration_ms);
+                    }
+                    info!(
+                        "Loop iteration {}/{} for step '{}': {}",
+                        i + 1,
+                        max,
+                        step.name,
+                        iteration_outcome(r)
+                    );
+                }
 
                 // Check until condition
                 if let Some(ref until_expr) = loop_cfg.until {
@@ -156,7 +428,8 @@ impl DefaultTestRunner {
                                 "Loop until condition met: {}",
                                 until_expr
                             );
-                            return Ok(result);
+                            finished = Some(result);
+                            break;
                         }
                         Ok(false) => {}
                         Err(e) => {
@@ -165,11 +438,15 @@ impl DefaultTestRunner {
                     }
                 }
 
-                // If no until condition, stop on success
-                if loop_cfg.until.is_none() {
+                // If no until condition, stop on success (unless
+                // timing is collected across all iterations)
+                if loop_cfg.until.is_none()
+                    && step.expect.loop_timing.is_none()
+                {
                     if let Some(ref r) = result {
                         if r.success {
-                            return Ok(result);
+                            finished = Some(result);
+                            break;
                         }
                     }
                 }
@@ -190,8 +467,53 @@ impl DefaultTestRunner {
                 }
             }
 
-            // All iterations exhausted — return last result
-            Ok(last_result)
+            vars.remove(LOOP_INDEX_VAR);
+
+            // All iterations exhausted: keep the last iteration's
+            // result rather than sending another request
+            let until_met = finished.is_some();
+            let mut result = finished.unwrap_or(last_result);
+            if let Some(r) = result.as_mut() {
+                r.attempts = attempts;
+            }
+            if let (Some(until_expr), false, Some(r)) =
+                (&loop_cfg.until, until_met, result.as_mut())
+            {
+                if !r.skipped {
+                    warn!(
+                        "Loop until condition not met after {} \
+                         iterations: {}",
+                        max, until_expr
+                    );
+                    let message = format!(
+                        "until condition not met after {max} iterations"
+                    );
+                    r.error = Some(match r.error.take() {
+                        Some(last) => {
+                            format!("{message} (last error: {last})")
+                        }
+                        None => message,
+                    });
+                    r.success = false;
+                }
+            }
+            if let (Some(timing), Some(r)) =
+                (&step.expect.loop_timing, result.as_mut())
+            {
+                let errors = check_loop_timing(timing, &durations);
+                if !errors.is_empty() {
+                    r.success = false;
+                    r.error = Some(
+                        r.error
+                            .take()
+                            .into_iter()
+                            .chain(errors)
+                            .collect::<Vec<_>>()
+                            .join("; "),
+                    );
+                }
+            }
+            Ok(result)
         } else {
             self.execute_step_once(
                 step,
@@ -206,6 +528,84 @@ impl DefaultTestRunner {
         }
     }
 
+    /// Execute a step, re-running it per `step.retry` while it
+    /// fails.
+    ///
+    /// Unlike `loop_config`, any failure is retryable, including
+    /// `expect`/`test` validation. Each attempt reuses the same
+    /// step key, so later steps see only the final attempt.
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_step_with_retry(
+        &self,
+        step: &TestStep,
+        vars: &mut HashMap<String, Value>,
+        config: &TestConfig,
+        steps_map: &mut Map<String, Value>,
+        step_idx: usize,
+        step_key_counts: &mut HashMap<String, usize>,
+        previous_value: &mut Option<Value>,
+    ) -> Result<Option<StepResult>> {
+        // A dry run prints each request once: no polling or retries
+        if self.dry_run {
+            return self
+                .execute_step_once(
+                    step,
+                    vars,
+                    config,
+                    steps_map,
+                    step_idx,
+                    step_key_counts,
+                    previous_value,
+                )
+                .await;
+        }
+        let Some(ref retry) = step.retry else {
+            return self
+                .execute_step_with_loop(
+                    step,
+                    vars,
+                    config,
+                    steps_map,
+                    step_idx,
+                    step_key_counts,
+                    previous_value,
+                )
+                .await;
+        };
+
+        let key_counts = step_key_counts.clone();
+        let mut interval = retry.interval;
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .execute_step_with_loop(
+                    step,
+                    vars,
+                    config,
+                    steps_map,
+                    step_idx,
+                    step_key_counts,
+                    previous_value,
+                )
+                .await?;
+            let failed = result.as_ref().is_some_and(|r| !r.success);
+            if !failed || attempt >= retry.count {
+                return Ok(result);
+            }
+
+            attempt += 1;
+            warn!(
+                "Step '{}' failed, retrying ({}/{})",
+                step.name, attempt, retry.count
+            );
+            tokio::time::sleep(Duration::from_secs_f64(interval)).await;
+            if let Some(backoff) = retry.backoff {
+                interval *= backoff;
+            }
+            *step_key_counts = key_counts.clone();
+        }
+    }
+
     fn get_value_by_path<'a>(
         value: &'a Value,
         path: &str,
@@ -279,34 +679,53 @@ impl DefaultTestRunner {
     }
 
     /// Expand `{{ key }}` or `{{ vars.key }}` placeholders in
-    /// `text` by looking up each captured key in `vars`.
-    ///
-    /// Uses a single pre-compiled regex instead of compiling one
-    /// regex per variable, reducing O(V) regex compilations to O(1).
+    /// `text`; see [`expand_placeholders`].
     fn expand_variables(
         &self,
         text: &str,
         vars: &HashMap<String, Value>,
     ) -> String {
-        use std::sync::LazyLock;
-
-        // Single regex that matches {{ key }} or {{ vars.key }}
-        // and captures the key name (group 1).
-        static PLACEHOLDER_RE: LazyLock<Regex> = LazyLock::new(|| {
-            Regex::new(r"\{\{\s*(?:vars\.)?(.+?)\s*\}\}")
-                .expect("failed to compile placeholder regex")
-        });
+        expand_placeholders(text, vars)
+    }
 
-        PLACEHOLDER_RE
-            .replace_all(text, |caps: &regex::Captures| {
-                let key = &caps[1];
-                match vars.get(key) {
-                    Some(Value::String(s)) => s.clone(),
-                    Some(v) => v.to_string(),
-                    None => caps[0].to_string(),
-                }
-            })
-            .into_owned()
+    /// Expand placeholders in every string (and object key) of a
+    /// JSON value, leaving its structure intact. A string made of
+    /// a single known placeholder becomes the variable's value with
+    /// its JSON type, so `"{{ expected_id }}"` can match (or send) a
+    /// number or an object.
+    ///
+    /// Expanding the serialized JSON text instead would break on
+    /// values containing quotes and on `\{{` escapes, whose
+    /// backslash is doubled in JSON.
+    fn expand_json_value(
+        &self,
+        value: &Value,
+        vars: &HashMap<String, Value>,
+    ) -> Value {
+        match value {
+            Value::String(s) => {
+                whole_placeholder_value(s, vars).cloned().unwrap_or_else(
+                    || Value::String(self.expand_variables(s, vars)),
+                )
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|v| self.expand_json_value(v, vars))
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| {
+                        (
+                            self.expand_variables(k, vars),
+                            self.expand_json_value(v, vars),
+                        )
+                    })
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
     }
 
     /// TODO: add English documentation
@@ -329,11 +748,43 @@ impl DefaultTestRunner {
         request: &HttpRequest,
         vars: &HashMap<String, Value>,
         config: &TestConfig,
+        request_id: Option<(&str, String)>,
+        timeout_secs: u64,
     ) -> Result<(Response, RequestInfo)> {
+        let (req_builder, req_info) = self.build_request(
+            request,
+            vars,
+            config,
+            request_id,
+            timeout_secs,
+        )?;
+
+        // TODO: add English comment
+        debug!("Sending request to {}", req_info.url);
+        let response =
+            req_builder.send().await.context("Failed to send request")?;
+        debug!("Received response with status: {}", response.status());
+
+        Ok((response, req_info))
+    }
+
+    /// Resolve `request` against `vars` and `config` into a
+    /// ready-to-send builder plus the [`RequestInfo`] describing it.
+    fn build_request(
+        &self,
+        request: &HttpRequest,
+        vars: &HashMap<String, Value>,
+        config: &TestConfig,
+        request_id: Option<(&str, String)>,
+        timeout_secs: u64,
+    ) -> Result<(RequestBuilder, RequestInfo)> {
         // TODO: add English comment
         let mut url = self.expand_variables(&request.url, vars);
 
         if !url.contains("://") {
+            if let Some(base_path) = &config.base_path {
+                url = join_base_path(base_path, &url);
+            }
             if let Some(base_url) = &config.base_url {
                 let base = reqwest::Url::parse(base_url).context(format!(
                     "Invalid base_url provided in scenario config: {base_url}"
@@ -362,43 +813,145 @@ impl DefaultTestRunner {
             }
         }
 
-        let headers: HashMap<String, String> = headers
+        let mut headers: HashMap<String, String> = headers
             .into_iter()
             .map(|(k, v)| (k, self.expand_variables(&v, vars)))
             .collect();
 
+        if let Some(auth) = &config.auth {
+            if !headers
+                .keys()
+                .any(|k| k.eq_ignore_ascii_case("authorization"))
+            {
+                headers.insert(
+                    "Authorization".to_string(),
+                    self.authorization_header(auth, vars),
+                );
+            }
+        }
+
+        if let Some(accept) = &config.default_accept {
+            if !headers.keys().any(|k| k.eq_ignore_ascii_case("accept")) {
+                headers.insert("Accept".to_string(), accept.clone());
+            }
+        }
+
+        if let Some((name, id)) = request_id {
+            if !headers.keys().any(|k| k.eq_ignore_ascii_case(name)) {
+                headers.insert(name.to_string(), id);
+            }
+        }
+
         // TODO: add English comment
         let mut req_builder = self
-            .client
+            .client_for(config)
             .request(self.convert_method(&request.method), &url)
-            .timeout(Duration::from_secs(config.timeout));
+            .timeout(Duration::from_secs(timeout_secs));
 
         // TODO: add English comment
         for (name, value) in &headers {
             req_builder = req_builder.header(name, value);
         }
 
-        // TODO: add English comment
-        if !request.query.is_empty() {
-            let query: HashMap<String, String> = request
-                .query
-                .iter()
-                .map(|(k, v)| (k.clone(), self.expand_variables(v, vars)))
-                .collect();
-
+        // Expanded query parameters (also captured in RequestInfo)
+        let query: HashMap<String, String> = request
+            .query
+            .iter()
+            .map(|(k, v)| (k.clone(), self.expand_variables(v, vars)))
+            .collect();
+        if !query.is_empty() {
             req_builder = req_builder.query(&query);
         }
 
-        // TODO: add English comment
+        // Inline `body`, or the contents of `body_file`: JSON goes
+        // through `body_type`, other files are sent as raw text
+        if request.body.is_some() && request.body_file.is_some() {
+            return Err(anyhow!(
+                "body and body_file cannot be used together"
+            ));
+        }
+        let mut json_body = request
+            .body
+            .as_ref()
+            .map(|body| self.expand_json_value(body, vars));
+        let mut text_body = None;
+        if let Some(body_file) = &request.body_file {
+            let (path, contents) =
+                self.read_body_file(body_file, vars, config)?;
+            if path.extension().is_some_and(|e| e == "json") {
+                // Parse before expanding, so values are inserted as
+                // JSON rather than spliced into the text
+                let parsed: Value = serde_json::from_str(&contents)
+                    .context(format!(
+                        "Failed to parse JSON body_file: {}",
+                        path.display()
+                    ))?;
+                json_body = Some(self.expand_json_value(&parsed, vars));
+            } else {
+                text_body = Some(self.expand_variables(&contents, vars));
+            }
+        }
+
         let mut body_str = None;
-        if let Some(body) = &request.body {
-            // TODO: add English comment
-            let body_json = serde_json::to_string(body)?;
-            let expanded_body = self.expand_variables(&body_json, vars);
-            body_str = Some(expanded_body.clone());
-            req_builder = req_builder
-                .header("Content-Type", "application/json")
-                .body(expanded_body);
+        if let Some(text) = text_body {
+            if !headers
+                .keys()
+                .any(|k| k.eq_ignore_ascii_case("content-type"))
+            {
+                req_builder =
+                    req_builder.header("Content-Type", "text/plain");
+            }
+            body_str = Some(text.clone());
+            req_builder = match request.compress {
+                Some(compression) => req_builder
+                    .header("Content-Encoding", compression.encoding())
+                    .body(compress_body(compression, &text)?),
+                None => req_builder.body(text),
+            };
+        }
+        if let Some(body) = &json_body {
+            let expanded_json = serde_json::to_string(body)?;
+            match request.body_type {
+                BodyType::Multipart => {
+                    let form = build_multipart_form(
+                        &serde_json::from_str(&expanded_json)?,
+                    )?;
+                    if request.compress.is_some() {
+                        warn!("compress is ignored for multipart bodies");
+                    }
+                    body_str = Some(expanded_json);
+                    // reqwest sets Content-Type with the boundary
+                    req_builder = req_builder.multipart(form);
+                }
+                body_type => {
+                    let (content_type, expanded_body) =
+                        if body_type == BodyType::Form {
+                            (
+                                "application/x-www-form-urlencoded",
+                                encode_form_body(&serde_json::from_str(
+                                    &expanded_json,
+                                )?)?,
+                            )
+                        } else {
+                            ("application/json", expanded_json)
+                        };
+                    body_str = Some(expanded_body.clone());
+                    req_builder =
+                        req_builder.header("Content-Type", content_type);
+                    req_builder = match request.compress {
+                        Some(compression) => req_builder
+                            .header(
+                                "Content-Encoding",
+                                compression.encoding(),
+                            )
+                            .body(compress_body(
+                                compression,
+                                &expanded_body,
+                            )?),
+                        None => req_builder.body(expanded_body),
+                    };
+                }
+            }
         }
 
         // TODO: add English comment
@@ -406,47 +959,277 @@ impl DefaultTestRunner {
             method: format!("{:?}", request.method),
             url: url.clone(),
             headers: headers.clone(),
+            query,
             body: body_str,
         };
 
-        // TODO: add English comment
-        debug!("Sending request to {}", url);
-        let response =
-            req_builder.send().await.context("Failed to send request")?;
-        debug!("Received response with status: {}", response.status());
+        Ok((req_builder, req_info))
+    }
 
-        Ok((response, req_info))
+    /// Read `body_file`, with `{{ var }}` placeholders expanded in
+    /// its path. A relative path resolves against
+    /// [`TestConfig::base_dir`]. Returns the resolved path with the
+    /// unexpanded contents.
+    fn read_body_file(
+        &self,
+        body_file: &str,
+        vars: &HashMap<String, Value>,
+        config: &TestConfig,
+    ) -> Result<(PathBuf, String)> {
+        let path = PathBuf::from(self.expand_variables(body_file, vars));
+        let path = match &config.base_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path,
+        };
+        let contents = std::fs::read_to_string(&path).context(format!(
+            "Failed to read body_file: {}",
+            path.display()
+        ))?;
+        Ok((path, contents))
+    }
+
+    /// `Authorization` header value for `auth`, with credentials
+    /// expanded against `vars`.
+    fn authorization_header(
+        &self,
+        auth: &AuthConfig,
+        vars: &HashMap<String, Value>,
+    ) -> String {
+        match auth {
+            AuthConfig::Bearer { token } => {
+                format!("Bearer {}", self.expand_variables(token, vars))
+            }
+            AuthConfig::Basic { username, password } => {
+                let credentials = format!(
+                    "{}:{}",
+                    self.expand_variables(username, vars),
+                    self.expand_variables(password, vars)
+                );
+                format!("Basic {}", BASE64_STANDARD.encode(credentials))
+            }
+        }
+    }
+
+    /// Combine the failures of each declarative expectation group
+    /// according to `mode`, returning the step error if any.
+    ///
+    /// Status failures always fail the step. Under
+    /// [`MatchMode::All`] every group must pass and the last
+    /// failure is reported; under [`MatchMode::Any`] one passing
+    /// group is enough, and errors are reported only when every
+    /// group failed.
+    fn combine_expectation_groups(
+        mode: MatchMode,
+        status_errors: Vec<String>,
+        groups: Vec<Vec<String>>,
+    ) -> Option<String> {
+        match mode {
+            MatchMode::All => status_errors
+                .into_iter()
+                .chain(groups.into_iter().flatten())
+                .last(),
+            MatchMode::Any => {
+                if let Some(err) = status_errors.into_iter().last() {
+                    return Some(err);
+                }
+                if groups.is_empty() || groups.iter().any(|g| g.is_empty())
+                {
+                    return None;
+                }
+                let errors: Vec<String> =
+                    groups.into_iter().flatten().collect();
+                Some(format!(
+                    "どの期待値グループも成立しませんでした \
+                     (match: any): {}",
+                    errors.join("; ")
+                ))
+            }
+        }
+    }
+
+    /// Resolve a path with `*` segments (e.g. `data.items.*.id`),
+    /// where `*` expands to every element of an array (or every
+    /// value of an object).
+    ///
+    /// Returns the concrete path of each match with its value, or
+    /// `None` for elements where the rest of the path is missing.
+    /// Returns `None` overall when the path does not reach a
+    /// wildcard container.
+    fn get_values_by_wildcard_path<'a>(
+        value: &'a Value,
+        path: &str,
+    ) -> Option<Vec<(String, Option<&'a Value>)>> {
+        fn walk<'a>(
+            value: &'a Value,
+            parts: &[&str],
+            prefix: String,
+            in_wildcard: bool,
+            out: &mut Vec<(String, Option<&'a Value>)>,
+        ) -> bool {
+            let join = |segment: &str| {
+                if prefix.is_empty() {
+                    segment.to_string()
+                } else {
+                    format!("{prefix}.{segment}")
+                }
+            };
+            let Some((head, rest)) = parts.split_first() else {
+                out.push((prefix, Some(value)));
+                return true;
+            };
+            if *head == "*" {
+                match value {
+                    Value::Array(array) => {
+                        for (i, item) in array.iter().enumerate() {
+                            walk(
+                                item,
+                                rest,
+                                join(&i.to_string()),
+                                true,
+                                out,
+                            );
+                        }
+                    }
+                    Value::Object(map) => {
+                        for (key, item) in map {
+                            walk(item, rest, join(key), true, out);
+                        }
+                    }
+                    _ => return false,
+                }
+                return true;
+            }
+            let child = match value {
+                Value::Object(map) => map.get(*head),
+                Value::Array(array) => {
+                    head.parse::<usize>().ok().and_then(|i| array.get(i))
+                }
+                _ => None,
+            };
+            match child {
+                Some(child) => {
+                    walk(child, rest, join(head), in_wildcard, out)
+                }
+                None if in_wildcard => {
+                    out.push((join(head), None));
+                    true
+                }
+                None => false,
+            }
+        }
+
+        let path = path
+            .strip_prefix("$.")
+            .or_else(|| path.strip_prefix('$'))
+            .unwrap_or(path);
+        let parts: Vec<&str> =
+            path.split('.').filter(|p| !p.is_empty()).collect();
+        let mut out = Vec::new();
+        walk(value, &parts, String::new(), false, &mut out).then_some(out)
     }
 
-    /// Extract values from the response body and store them as
-    /// variables for subsequent steps.
-    async fn save_variables(
+    /// Check that `expected` holds for every element matched by a
+    /// wildcard `path`, reporting the first element that fails.
+    /// Vacuously passes when the wildcard matches no elements.
+    fn check_wildcard_json(
+        json_body: &Value,
+        path: &str,
+        expected: &Value,
+        opts: crate::validator::CompareOptions,
+    ) -> Option<String> {
+        let Some(matches) =
+            Self::get_values_by_wildcard_path(json_body, path)
+        else {
+            return Some(format!(
+                "JSONパス '{path}' が\
+                 レスポンスに存在しません"
+            ));
+        };
+        matches
+            .into_iter()
+            .find_map(|(concrete, actual)| match actual {
+                Some(actual)
+                    if crate::validator::values_equal(
+                        actual, expected, opts,
+                    ) =>
+                {
+                    None
+                }
+                Some(actual) => Some(format!(
+                    "JSONパス '{path}' の要素 '{concrete}' の値が\
+                 期待値と一致しません。\
+                 期待: {expected:?}, \
+                 実際: {actual:?}"
+                )),
+                None => Some(format!(
+                    "JSONパス '{path}' の要素 '{concrete}' が\
+                 レスポンスに存在しません"
+                )),
+            })
+    }
+
+    /// Save `save` entries into `vars`: `header.<Name>` paths read
+    /// the response header (see [`response_header_name`]), the special
+    /// paths `__status__`, `__body__` and `__headers__` bind the
+    /// whole status, raw body or header map, and anything else is
+    /// a JSON path into the body.
+    ///
+    /// Each entry is saved independently: one that cannot be
+    /// resolved (including a JSON path on a non-JSON body) is
+    /// logged and skipped without affecting the others.
+    fn save_variables(
         &self,
         save: &HashMap<String, String>,
+        status: u16,
+        headers: &HashMap<String, String>,
         body: &str,
         vars: &mut HashMap<String, Value>,
-    ) -> Result<()> {
-        if save.is_empty() {
-            return Ok(());
-        }
-
-        let json_body: Value = serde_json::from_str(body)
-            .context("Failed to parse response as JSON")?;
+    ) {
+        // Parsed on first use so header/special saves work for any
+        // body
+        let mut json_body: Option<Result<Value, String>> = None;
 
         for (var_name, json_path) in save {
-            let current = Self::get_value_by_path(&json_body, json_path)
-                .ok_or_else(|| {
-                    anyhow!("JSON path '{json_path}' not found in response")
-                })?;
+            let var_name = self.expand_variables(var_name, vars);
+            let current = if let Some(value) =
+                special_save_value(json_path, status, headers, body)
+            {
+                Ok(value)
+            } else if let Some(name) = response_header_name(json_path) {
+                headers
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                    .map(|(_, v)| Value::String(v.clone()))
+                    .ok_or_else(|| format!("header '{name}' not found"))
+            } else {
+                match json_body.get_or_insert_with(|| {
+                    serde_json::from_str(body).map_err(|e| {
+                        format!("response body is not JSON ({e})")
+                    })
+                }) {
+                    Ok(json) => Self::get_value_by_path(json, json_path)
+                        .cloned()
+                        .ok_or_else(|| {
+                            format!("JSON path '{json_path}' not found")
+                        }),
+                    Err(e) => Err(e.clone()),
+                }
+            };
 
-            vars.insert(var_name.clone(), current.clone());
-            debug!(
-                "Saved variable '{}' with value: {:?}",
-                var_name, current
-            );
+            match current {
+                Ok(current) => {
+                    debug!(
+                        "Saved variable '{}' with value: {:?}",
+                        var_name, current
+                    );
+                    vars.insert(var_name, current);
+                }
+                Err(e) => warn!(
+                    "Failed to save variable '{}' from '{}': {}",
+                    var_name, json_path, e
+                ),
+            }
         }
-
-        Ok(())
     }
 }
 
@@ -492,11 +1275,9 @@ impl DefaultTestRunner {
             // Override included scenario vars with provided
             // ones
             for (k, v) in &include.vars {
-                let json_str = serde_json::to_string(v)?;
-                let expanded = self.expand_variables(&json_str, vars);
-                if let Ok(val) = serde_json::from_str::<Value>(&expanded) {
-                    included.vars.insert(k.clone(), val);
-                }
+                included
+                    .vars
+                    .insert(k.clone(), self.expand_json_value(v, vars));
             }
 
             // Copy parent vars to included scenario
@@ -549,25 +1330,142 @@ impl DefaultTestRunner {
                     method: "INCLUDE".to_string(),
                     url: include_path,
                     headers: HashMap::new(),
+                    query: HashMap::new(),
                     body: None,
                 },
                 response: None,
                 duration_ms: step_start.elapsed().as_millis() as u64,
+                vars_snapshot: self.vars_snapshot(result.success, vars),
+                skipped: false,
+                skip_reason: None,
+                assertions: Vec::new(),
+                diff: Vec::new(),
+                attempts: 1,
             }));
         }
 
-        // Condition check
-        if let Some(condition) = &step.condition {
-            let expanded_condition = self.expand_variables(condition, vars);
-            if expanded_condition.trim().to_lowercase() != "true" {
-                debug!("Skipping step due to condition: {}", condition);
-                return Ok(None);
-            }
+        // `previous` is the prior step's `current`, so this step's
+        // condition and request can already reference it
+        if let Some(prev) = previous_value.as_ref() {
+            vars.insert("previous".to_string(), prev.clone());
         }
 
-        // Send request
-        let send_result =
-            self.send_request(&step.request, vars, config).await;
+        // Condition check: skip when it does not hold, fail the
+        // step when it cannot be evaluated
+        if let Some(condition) = &step.condition {
+            let expanded_condition = self.expand_variables(condition, vars);
+            let outcome = match expanded_condition.trim() {
+                c if c.eq_ignore_ascii_case("true") => Ok(true),
+                c if c.eq_ignore_ascii_case("false") => Ok(false),
+                c => expression::evaluate_test(c, vars),
+            };
+            let (success, error, skip_reason) = match outcome {
+                Ok(true) => (true, None, None),
+                Ok(false) => {
+                    debug!("Skipping step due to condition: {}", condition);
+                    (
+                        true,
+                        None,
+                        Some(format!("condition not met: {condition}")),
+                    )
+                }
+                Err(e) => {
+                    error!(
+                        "Condition of step '{}' failed to evaluate: {}",
+                        step.name, e
+                    );
+                    (false, Some(format!("condition error: {e}")), None)
+                }
+            };
+            if !success || skip_reason.is_some() {
+                return Ok(Some(StepResult {
+                    name: step.name.clone(),
+                    success,
+                    error,
+                    request: RequestInfo {
+                        method: format!("{:?}", step.request.method),
+                        url: self.expand_variables(&step.request.url, vars),
+                        headers: HashMap::new(),
+                        query: HashMap::new(),
+                        body: None,
+                    },
+                    response: None,
+                    duration_ms: 0,
+                    vars_snapshot: self.vars_snapshot(success, vars),
+                    skipped: skip_reason.is_some(),
+                    skip_reason,
+                    assertions: Vec::new(),
+                    diff: Vec::new(),
+                    attempts: 1,
+                }));
+            }
+        }
+
+        // Request ID injection
+        let request_id = match &config.inject_request_id {
+            Some(header) if step.inject_request_id != Some(false) => {
+                let id = uuid::Uuid::new_v4().to_string();
+                vars.insert(
+                    REQUEST_ID_VAR.to_string(),
+                    Value::String(id.clone()),
+                );
+                Some((header.as_str(), id))
+            }
+            _ => None,
+        };
+
+        let timeout_secs =
+            step.timeout.filter(|t| *t > 0).unwrap_or(config.timeout);
+
+        if self.dry_run {
+            let built = self.build_request(
+                &step.request,
+                vars,
+                config,
+                request_id,
+                timeout_secs,
+            );
+            let (success, error, request) = match built {
+                Ok((_, req_info)) => (true, None, req_info),
+                Err(err) => (
+                    false,
+                    Some(format!("failed to build request: {err:#}")),
+                    RequestInfo {
+                        method: format!("{:?}", step.request.method),
+                        url: self.expand_variables(&step.request.url, vars),
+                        headers: HashMap::new(),
+                        query: HashMap::new(),
+                        body: None,
+                    },
+                ),
+            };
+            return Ok(Some(StepResult {
+                name: step.name.clone(),
+                success,
+                error,
+                request,
+                response: None,
+                duration_ms: 0,
+                vars_snapshot: None,
+                skipped: success,
+                skip_reason: success.then(|| "dry run".to_string()),
+                assertions: Vec::new(),
+                diff: Vec::new(),
+                attempts: 1,
+            }));
+        }
+
+        // Send request
+        let request_start = Instant::now();
+        let send_result = self
+            .send_request(
+                &step.request,
+                vars,
+                config,
+                request_id,
+                timeout_secs,
+            )
+            .await;
 
         let (response, req_info) = match send_result {
             Ok(res) => res,
@@ -581,32 +1479,60 @@ impl DefaultTestRunner {
                         method: format!("{:?}", step.request.method),
                         url: self.expand_variables(&step.request.url, vars),
                         headers: HashMap::new(),
+                        query: HashMap::new(),
                         body: None,
                     },
                     response: None,
                     duration_ms: step_start.elapsed().as_millis() as u64,
+                    vars_snapshot: self.vars_snapshot(false, vars),
+                    skipped: false,
+                    skip_reason: None,
+                    assertions: Vec::new(),
+                    diff: Vec::new(),
+                    attempts: 1,
                 }));
             }
         };
 
         let status = response.status().as_u16();
-        let headers: HashMap<String, String> = response
-            .headers()
+        let http_version = format!("{:?}", response.version());
+        let certificate = response
+            .extensions()
+            .get::<reqwest::tls::TlsInfo>()
+            .and_then(|info| info.peer_certificate())
+            .map(crate::tls::parse_certificate)
+            .transpose()
+            .unwrap_or_else(|e| {
+                warn!("{}", e);
+                None
+            });
+        // Keep every value of repeated headers (e.g. multiple
+        // `Set-Cookie`), so expectations can match any of them.
+        let mut header_values: HashMap<String, Vec<String>> =
+            HashMap::new();
+        for (name, value) in response.headers() {
+            header_values
+                .entry(name.to_string())
+                .or_default()
+                .push(value.to_str().unwrap_or("").to_string());
+        }
+        let headers: HashMap<String, String> = header_values
             .iter()
-            .map(|(name, value)| {
-                (name.to_string(), value.to_str().unwrap_or("").to_string())
-            })
+            .map(|(name, values)| (name.clone(), values.join(", ")))
             .collect();
 
         let body = response
             .text()
             .await
             .context("Failed to read response body")?;
+        let elapsed_ms = request_start.elapsed().as_millis() as u64;
 
         let response_info = Some(ResponseInfo {
             status,
             headers: headers.clone(),
             body: Some(body.clone()),
+            http_version: Some(http_version.clone()),
+            certificate: certificate.clone(),
         });
 
         let parsed_json = serde_json::from_str::<Value>(&body).ok();
@@ -653,6 +1579,12 @@ impl DefaultTestRunner {
                     Self::map_string_to_value(&req_info.headers),
                 );
             }
+            if !req_info.query.is_empty() {
+                req_map.insert(
+                    "query".into(),
+                    Self::map_string_to_value(&req_info.query),
+                );
+            }
             if let Some(body) = &req_info.body {
                 req_map.insert("body".into(), Value::String(body.clone()));
             }
@@ -694,55 +1626,233 @@ impl DefaultTestRunner {
             &parsed_json,
             &body,
             &req_info,
+            elapsed_ms,
         );
         vars.insert("current".to_string(), current_value.clone());
 
-        // Set `previous` from prior step
-        if let Some(ref prev) = previous_value {
-            vars.insert("previous".to_string(), prev.clone());
-        }
-
         // ── Declarative `expect:` validation ────────────
 
-        // Status code
-        if status != step.expect.status {
-            step_success = false;
-            step_error = Some(format!(
+        // Status code (always required, regardless of `match`)
+        let mut log = AssertionLog::new(self.explain);
+        let mut diff = Vec::new();
+        let mut status_errors: Vec<String> = Vec::new();
+        if !step.expect.status_matches(status) {
+            status_errors.push(format!(
                 "ステータスコードが期待値と一致しません。\
                  期待: {}, 実際: {}",
-                step.expect.status, status
+                step.expect.expected_status_label(),
+                status
             ));
         }
+        log.record(
+            "status",
+            &status_errors,
+            0,
+            Some(step.expect.expected_status_label()),
+            Some(status.to_string()),
+        );
 
-        // Headers
+        // Remaining expectation groups, combined per `match`
+        let mut groups: Vec<Vec<String>> = Vec::new();
+
+        // Headers (a repeated header matches if any value does)
+        let mut header_errors: Vec<String> = Vec::new();
         for (name, expected) in &step.expect.headers {
+            let expected = &self.expand_variables(expected, vars);
+            let before = header_errors.len();
             if let Some(actual) = headers.get(name) {
-                if actual != expected {
-                    step_success = false;
-                    step_error = Some(format!(
+                let any_matches = actual == expected
+                    || header_values
+                        .get(name)
+                        .is_some_and(|values| values.contains(expected));
+                if !any_matches {
+                    header_errors.push(format!(
                         "ヘッダー '{name}' の値が期待値と一致しません。\
                          期待: {expected}, 実際: {actual}"
                     ));
                 }
             } else {
-                step_success = false;
-                step_error = Some(format!(
+                header_errors.push(format!(
                     "ヘッダー '{name}' がレスポンスに存在しません"
                 ));
             }
+            let shown = |value: &str| {
+                if crate::redact::is_sensitive_header(name) {
+                    crate::redact::REDACTED.to_string()
+                } else {
+                    value.to_string()
+                }
+            };
+            log.record(
+                format!("header {name}"),
+                &header_errors,
+                before,
+                Some(shown(expected)),
+                headers.get(name).map(|v| shown(v)),
+            );
+        }
+        for (name, expected) in &step.expect.headers_contain {
+            let expected = &self.expand_variables(expected, vars);
+            let needle = expected.to_lowercase();
+            let key = name.to_ascii_lowercase();
+            let shown = |value: &str| {
+                if crate::redact::is_sensitive_header(name) {
+                    crate::redact::REDACTED.to_string()
+                } else {
+                    value.to_string()
+                }
+            };
+            let before = header_errors.len();
+            match header_values.get(&key) {
+                Some(values) => {
+                    if !values
+                        .iter()
+                        .any(|v| v.to_lowercase().contains(&needle))
+                    {
+                        header_errors.push(format!(
+                            "headers_contain: ヘッダー '{name}' に \
+                             '{}' が含まれていません (実際: {})",
+                            shown(expected),
+                            shown(&headers[&key])
+                        ));
+                    }
+                }
+                None => header_errors.push(format!(
+                    "headers_contain: ヘッダー '{name}' が\
+                     レスポンスに存在しません"
+                )),
+            }
+            log.record(
+                format!("headers_contain {name}"),
+                &header_errors,
+                before,
+                Some(shown(expected)),
+                headers.get(&key).map(|v| shown(v)),
+            );
+        }
+        if !step.expect.headers.is_empty()
+            || !step.expect.headers_contain.is_empty()
+        {
+            groups.push(header_errors);
+        }
+
+        // TLS certificate
+        if let Some(expected) = &step.expect.cert {
+            let cert_errors = match &certificate {
+                Some(cert) => {
+                    let errors = crate::tls::check_certificate(
+                        expected,
+                        cert,
+                        Utc::now(),
+                    );
+                    if errors.is_empty() {
+                        errors
+                    } else {
+                        vec![errors.join("; ")]
+                    }
+                }
+                None => vec!["cert: サーバー証明書を取得できません \
+                     (HTTPSレスポンスではないか、tls_info なしで\
+                     クライアントが構築されています)"
+                    .to_string()],
+            };
+            log.record_group("cert", &cert_errors);
+            groups.push(cert_errors);
+        }
+
+        // HTTP version
+        if let Some(expected) = &step.expect.http_version {
+            let mut version_errors: Vec<String> = Vec::new();
+            if !http_version_matches(expected, &http_version) {
+                version_errors.push(format!(
+                    "HTTPバージョンが期待値と一致しません。\
+                     期待: {expected}, 実際: {http_version}"
+                ));
+            }
+            log.record(
+                "http_version",
+                &version_errors,
+                0,
+                Some(expected.clone()),
+                Some(http_version.clone()),
+            );
+            groups.push(version_errors);
+        }
+
+        // Request round-trip time
+        if let Some(max_ms) = step.expect.max_duration_ms {
+            let mut duration_errors: Vec<String> = Vec::new();
+            if elapsed_ms > max_ms {
+                duration_errors.push(format!(
+                    "リクエストに {elapsed_ms} ms かかり、\
+                     max_duration_ms {max_ms} を超えました"
+                ));
+            }
+            log.record(
+                "max_duration_ms",
+                &duration_errors,
+                0,
+                Some(max_ms.to_string()),
+                Some(elapsed_ms.to_string()),
+            );
+            groups.push(duration_errors);
+        }
+
+        // Rate-limit headers
+        if let Some(rate_limit) = &step.expect.rate_limit {
+            let errors = check_rate_limit(rate_limit, &headers);
+            let rate_errors = if errors.is_empty() {
+                errors
+            } else {
+                vec![errors.join("; ")]
+            };
+            log.record_group("rate_limit", &rate_errors);
+            groups.push(rate_errors);
         }
 
+        let compare_opts = crate::validator::CompareOptions {
+            numeric_loose: config.numeric_loose,
+            coerce_numeric_strings: config.coerce_numeric_strings,
+        };
+
         // JSON path validation
         if !step.expect.json.is_empty()
+            || !step.expect.json_ne.is_empty()
+            || !step.expect.json_exists.is_empty()
+            || !step.expect.json_absent.is_empty()
             || !step.expect.json_lengths.is_empty()
+            || !step.expect.json_in.is_empty()
+            || !step.expect.json_approx.is_empty()
         {
+            let mut json_errors: Vec<String> = Vec::new();
             if let Some(json_body) = &parsed_json {
                 for (path, expected) in &step.expect.json {
+                    let expected = &self.expand_json_value(expected, vars);
+                    let before = json_errors.len();
+                    if path.split('.').any(|part| part == "*") {
+                        json_errors.extend(Self::check_wildcard_json(
+                            json_body,
+                            path,
+                            expected,
+                            compare_opts,
+                        ));
+                        log.record(
+                            format!("json {path}"),
+                            &json_errors,
+                            before,
+                            Some(expected.to_string()),
+                            None,
+                        );
+                        continue;
+                    }
                     match Self::get_value_by_path(json_body, path) {
                         Some(actual) => {
-                            if actual != expected {
-                                step_success = false;
-                                step_error = Some(format!(
+                            if !crate::validator::values_equal(
+                                actual,
+                                expected,
+                                compare_opts,
+                            ) {
+                                json_errors.push(format!(
                                     "JSONパス '{path}' の値が\
                                      期待値と一致しません。\
                                      期待: {expected:?}, \
@@ -751,21 +1861,158 @@ impl DefaultTestRunner {
                             }
                         }
                         None => {
-                            step_success = false;
-                            step_error = Some(format!(
+                            json_errors.push(format!(
                                 "JSONパス '{path}' が\
                                  レスポンスに存在しません"
                             ));
                         }
                     }
+                    log.record(
+                        format!("json {path}"),
+                        &json_errors,
+                        before,
+                        Some(expected.to_string()),
+                        Self::get_value_by_path(json_body, path)
+                            .map(Value::to_string),
+                    );
+                }
+
+                for (path, unexpected) in &step.expect.json_ne {
+                    let unexpected =
+                        &self.expand_json_value(unexpected, vars);
+                    let actual = Self::get_value_by_path(json_body, path);
+                    let before = json_errors.len();
+                    if actual.is_some_and(|actual| {
+                        crate::validator::values_equal(
+                            actual,
+                            unexpected,
+                            compare_opts,
+                        )
+                    }) {
+                        json_errors.push(format!(
+                            "json_ne: JSONパス '{path}' の値が \
+                             {unexpected} と一致しています"
+                        ));
+                    }
+                    log.record(
+                        format!("json_ne {path}"),
+                        &json_errors,
+                        before,
+                        Some(format!("not {unexpected}")),
+                        actual.map(Value::to_string),
+                    );
+                }
+
+                for path in &step.expect.json_exists {
+                    let actual = Self::get_value_by_path(json_body, path);
+                    let before = json_errors.len();
+                    match actual {
+                        None => json_errors.push(format!(
+                            "json_exists: JSONパス '{path}' が\
+                             レスポンスに存在しません"
+                        )),
+                        Some(Value::Null) => json_errors.push(format!(
+                            "json_exists: JSONパス '{path}' の値が null です"
+                        )),
+                        Some(_) => {}
+                    }
+                    log.record(
+                        format!("json_exists {path}"),
+                        &json_errors,
+                        before,
+                        None,
+                        actual.map(Value::to_string),
+                    );
+                }
+
+                for path in &step.expect.json_absent {
+                    let actual = Self::get_value_by_path(json_body, path);
+                    let before = json_errors.len();
+                    if let Some(actual) = actual {
+                        json_errors.push(format!(
+                            "json_absent: JSONパス '{path}' が\
+                             レスポンスに存在します (実際: {actual})"
+                        ));
+                    }
+                    log.record(
+                        format!("json_absent {path}"),
+                        &json_errors,
+                        before,
+                        None,
+                        actual.map(Value::to_string),
+                    );
+                }
+
+                for (path, allowed) in &step.expect.json_in {
+                    let allowed: Vec<Value> = allowed
+                        .iter()
+                        .map(|v| self.expand_json_value(v, vars))
+                        .collect();
+                    let before = json_errors.len();
+                    match Self::get_value_by_path(json_body, path) {
+                        Some(actual) => {
+                            let any_equal =
+                                allowed.iter().any(|expected| {
+                                    crate::validator::values_equal(
+                                        actual,
+                                        expected,
+                                        compare_opts,
+                                    )
+                                });
+                            if !any_equal {
+                                json_errors.push(format!(
+                                    "JSONパス '{path}' の値が\
+                                     許可された値のいずれとも\
+                                     一致しません。\
+                                     許可: {allowed:?}, \
+                                     実際: {actual:?}"
+                                ));
+                            }
+                        }
+                        None => {
+                            json_errors.push(format!(
+                                "JSONパス '{path}' が\
+                                 レスポンスに存在しません"
+                            ));
+                        }
+                    }
+                    log.record(
+                        format!("json_in {path}"),
+                        &json_errors,
+                        before,
+                        Some(Value::Array(allowed.clone()).to_string()),
+                        Self::get_value_by_path(json_body, path)
+                            .map(Value::to_string),
+                    );
+                }
+
+                for (path, approx) in &step.expect.json_approx {
+                    let actual = Self::get_value_by_path(json_body, path);
+                    let before = json_errors.len();
+                    if let Some(err) =
+                        check_json_approx(actual, path, approx)
+                    {
+                        json_errors.push(err);
+                    }
+                    log.record(
+                        format!("json_approx {path}"),
+                        &json_errors,
+                        before,
+                        Some(format!(
+                            "{} ± {}",
+                            approx.value, approx.epsilon
+                        )),
+                        actual.map(Value::to_string),
+                    );
                 }
 
                 for (path, expected_len) in &step.expect.json_lengths {
+                    let actual = Self::get_value_by_path(json_body, path);
+                    let before = json_errors.len();
                     match Self::get_value_by_path(json_body, path) {
                         Some(Value::Array(array)) => {
                             if array.len() != *expected_len {
-                                step_success = false;
-                                step_error = Some(format!(
+                                json_errors.push(format!(
                                     "JSONパス '{path}' の配列長が\
                                      一致しません。\
                                      期待: {expected_len}, \
@@ -776,8 +2023,7 @@ impl DefaultTestRunner {
                         }
                         Some(Value::Object(obj)) => {
                             if obj.len() != *expected_len {
-                                step_success = false;
-                                step_error = Some(format!(
+                                json_errors.push(format!(
                                     "JSONパス '{path}' の\
                                      オブジェクト要素数が\
                                      一致しません。\
@@ -788,70 +2034,361 @@ impl DefaultTestRunner {
                             }
                         }
                         Some(other) => {
-                            step_success = false;
-                            step_error = Some(format!(
+                            json_errors.push(format!(
                                 "JSONパス '{path}' は配列\
                                  またはオブジェクトではありません \
                                  (実際: {other:?})"
                             ));
                         }
                         None => {
-                            step_success = false;
-                            step_error = Some(format!(
+                            json_errors.push(format!(
                                 "JSONパス '{path}' が\
                                  レスポンスに存在しません"
                             ));
                         }
                     }
+                    log.record(
+                        format!("json_lengths {path}"),
+                        &json_errors,
+                        before,
+                        Some(expected_len.to_string()),
+                        actual.and_then(|v| match v {
+                            Value::Array(a) => Some(a.len().to_string()),
+                            Value::Object(o) => Some(o.len().to_string()),
+                            _ => None,
+                        }),
+                    );
                 }
             } else {
-                step_success = false;
-                step_error = Some(
+                json_errors.push(
                     "レスポンスが有効なJSONではありません".to_string(),
                 );
+                log.record_group("json", &json_errors);
             }
+            groups.push(json_errors);
         }
 
         // json_eq — full equality check
         if let Some(ref exact_expected) = step.expect.json_eq {
+            let mut json_eq_errors: Vec<String> = Vec::new();
             if let Some(json_body) = &parsed_json {
-                let expanded_json = serde_json::to_string(exact_expected)?;
-                let expanded_str =
-                    self.expand_variables(&expanded_json, vars);
-                let expanded: Value = serde_json::from_str(&expanded_str)?;
-                let exact_errors = crate::validator::validate_data_eq(
+                let expanded = self.expand_json_value(exact_expected, vars);
+                let mismatches = crate::validator::diff_data_eq(
                     json_body,
                     &expanded,
                     &step.expect.json_ignore_fields,
                     "",
+                    compare_opts,
+                );
+                if !mismatches.is_empty() {
+                    let messages: Vec<String> = mismatches
+                        .iter()
+                        .map(DataMismatch::message)
+                        .collect();
+                    json_eq_errors.push(messages.join("; "));
+                    diff.extend(mismatches);
+                }
+            } else {
+                json_eq_errors.push(
+                    "json_eq: レスポンスが有効なJSONではありません"
+                        .to_string(),
                 );
-                if !exact_errors.is_empty() {
-                    step_success = false;
-                    step_error = Some(exact_errors.join("; "));
+            }
+            log.record_group("json_eq", &json_eq_errors);
+            groups.push(json_eq_errors);
+        }
+
+        // shape — required / forbidden paths
+        if let Some(shape) = &step.expect.shape {
+            let mut shape_errors: Vec<String> = Vec::new();
+            if let Some(json_body) = &parsed_json {
+                // Every violated path is reported in one message
+                let mut violations: Vec<String> = Vec::new();
+                for path in &shape.required {
+                    if Self::get_value_by_path(json_body, path).is_none() {
+                        violations.push(format!(
+                            "必須パス '{path}' が存在しません"
+                        ));
+                    }
+                }
+                for path in &shape.forbidden {
+                    if Self::get_value_by_path(json_body, path).is_some() {
+                        violations.push(format!(
+                            "禁止パス '{path}' が存在します"
+                        ));
+                    }
+                }
+                if !violations.is_empty() {
+                    shape_errors
+                        .push(format!("shape: {}", violations.join("; ")));
+                }
+            } else {
+                shape_errors.push(
+                    "shape: レスポンスが有効なJSONではありません"
+                        .to_string(),
+                );
+            }
+            log.record_group("shape", &shape_errors);
+            groups.push(shape_errors);
+        }
+
+        // json_decoded — structural check of string-encoded JSON
+        if !step.expect.json_decoded.is_empty() {
+            let mut decoded_errors: Vec<String> = Vec::new();
+            if let Some(json_body) = &parsed_json {
+                for (path, expected) in &step.expect.json_decoded {
+                    let expanded = self.expand_json_value(expected, vars);
+                    match Self::get_value_by_path(json_body, path) {
+                        Some(Value::String(raw)) => {
+                            match serde_json::from_str::<Value>(raw) {
+                                Ok(decoded) => {
+                                    let mismatches =
+                                        crate::validator::diff_data_eq(
+                                            &decoded,
+                                            &expanded,
+                                            &[],
+                                            path,
+                                            compare_opts,
+                                        );
+                                    if !mismatches.is_empty() {
+                                        let messages: Vec<String> =
+                                            mismatches
+                                                .iter()
+                                                .map(DataMismatch::message)
+                                                .collect();
+                                        decoded_errors
+                                            .push(messages.join("; "));
+                                        diff.extend(mismatches);
+                                    }
+                                }
+                                Err(e) => decoded_errors.push(format!(
+                                    "json_decoded: JSONパス '{path}' の\
+                                     値が有効なJSONではありません \
+                                     ({e}): {raw}"
+                                )),
+                            }
+                        }
+                        Some(other) => decoded_errors.push(format!(
+                            "json_decoded: JSONパス '{path}' の値が\
+                             文字列ではありません (実際: {other})"
+                        )),
+                        None => decoded_errors.push(format!(
+                            "json_decoded: JSONパス '{path}' が\
+                             レスポンスに存在しません"
+                        )),
+                    }
                 }
             } else {
-                step_success = false;
-                step_error =
-                    Some("json_eq: response is not valid JSON".to_string());
+                decoded_errors.push(
+                    "json_decoded: レスポンスが有効なJSONではありません"
+                        .to_string(),
+                );
             }
+            log.record_group("json_decoded", &decoded_errors);
+            groups.push(decoded_errors);
         }
 
-        // Contains
+        // xpath — element/attribute values of an XML body
+        if !step.expect.xpath.is_empty() {
+            let mut xpath_errors: Vec<String> = Vec::new();
+            match crate::xml::parse(&body) {
+                Ok(root) => {
+                    for (path, expected) in &step.expect.xpath {
+                        let expected =
+                            self.expand_variables(expected, vars);
+                        let before = xpath_errors.len();
+                        let actual = match crate::xml::select(&root, path) {
+                            Ok(actual) => actual,
+                            Err(e) => {
+                                xpath_errors.push(format!("xpath: {e}"));
+                                None
+                            }
+                        };
+                        match &actual {
+                            Some(actual) if *actual != expected => {
+                                xpath_errors.push(format!(
+                                    "xpath: パス '{path}' の値が\
+                                     期待値と一致しません。\
+                                     期待: {expected}, 実際: {actual}"
+                                ))
+                            }
+                            None if xpath_errors.len() == before => {
+                                xpath_errors.push(format!(
+                                    "xpath: パス '{path}' が\
+                                     レスポンスに存在しません"
+                                ))
+                            }
+                            _ => {}
+                        }
+                        log.record(
+                            format!("xpath {path}"),
+                            &xpath_errors,
+                            before,
+                            Some(expected),
+                            actual,
+                        );
+                    }
+                }
+                Err(e) => {
+                    xpath_errors.push(format!(
+                        "xpath: レスポンスが有効なXMLではありません ({e})"
+                    ));
+                    log.record_group("xpath", &xpath_errors);
+                }
+            }
+            groups.push(xpath_errors);
+        }
+
+        // csv — row count, header row and cell values of a CSV body
+        if let Some(csv) = &step.expect.csv {
+            let mut expected = csv.clone();
+            for value in expected.cell.values_mut() {
+                *value = self.expand_variables(value, vars);
+            }
+            let mut csv_errors: Vec<String> = Vec::new();
+            let errors = crate::validator::validate_csv(&body, &expected);
+            for err in &errors {
+                error!("{} (step: {})", err, step.name);
+            }
+            if !errors.is_empty() {
+                csv_errors.push(errors.join("; "));
+            }
+            log.record_group("csv", &csv_errors);
+            groups.push(csv_errors);
+        }
+
+        // JSON Schema (draft 2020-12)
+        if let Some(schema) = &step.expect.schema {
+            let mut schema_errors: Vec<String> = Vec::new();
+            match &parsed_json {
+                Some(json_body) => {
+                    let errors = crate::validator::validate_schema(
+                        json_body, schema,
+                    );
+                    for err in &errors {
+                        error!("{} (step: {})", err, step.name);
+                    }
+                    if !errors.is_empty() {
+                        schema_errors.push(errors.join("; "));
+                    }
+                }
+                None => schema_errors.push(
+                    "schema: レスポンスが有効なJSONではありません"
+                        .to_string(),
+                ),
+            }
+            log.record_group("schema", &schema_errors);
+            groups.push(schema_errors);
+        }
+
+        // Contains (`re:` entries are regexes, the rest literals)
+        let mut contains_errors: Vec<String> = Vec::new();
         for text in &step.expect.contains {
             let expanded_text = self.expand_variables(text, vars);
-            if !body.contains(&expanded_text) {
+            let before = contains_errors.len();
+            let (matched, label) = match contains_entry(&expanded_text) {
+                ContainsEntry::Literal(literal) => {
+                    (body.contains(literal), literal.to_string())
+                }
+                ContainsEntry::Regex(pattern) => {
+                    match Regex::new(pattern) {
+                        Ok(re) => {
+                            (re.is_match(&body), format!("re:{pattern}"))
+                        }
+                        Err(e) => {
+                            contains_errors.push(format!(
+                                "contains: 不正な正規表現 '{pattern}': {e}"
+                            ));
+                            log.record(
+                                format!("contains {expanded_text}"),
+                                &contains_errors,
+                                before,
+                                None,
+                                None,
+                            );
+                            continue;
+                        }
+                    }
+                }
+            };
+            if !matched {
                 error!(
                     "レスポンスボディに期待するテキスト \
-                     '{expanded_text}' が含まれていません \
+                     '{label}' が含まれていません \
                      (ステップ: {})",
                     step.name
                 );
-                step_success = false;
-                step_error = Some(format!(
+                contains_errors.push(format!(
                     "レスポンスボディに期待するテキスト \
-                     '{expanded_text}' が含まれていません"
+                     '{label}' が含まれていません"
                 ));
             }
+            log.record(
+                format!("contains {label}"),
+                &contains_errors,
+                before,
+                None,
+                None,
+            );
+        }
+        if !step.expect.contains.is_empty() {
+            groups.push(contains_errors);
+        }
+
+        // Regex matches against the raw body
+        let mut matches_errors: Vec<String> = Vec::new();
+        let mut match_captures: Vec<(String, String)> = Vec::new();
+        for (pattern, names) in &step.expect.matches {
+            let pattern = self.expand_variables(pattern, vars);
+            let before = matches_errors.len();
+            let re = match Regex::new(&pattern) {
+                Ok(re) => re,
+                Err(e) => {
+                    matches_errors.push(format!(
+                        "matches: 不正な正規表現 '{pattern}': {e}"
+                    ));
+                    log.record(
+                        format!("matches {pattern}"),
+                        &matches_errors,
+                        before,
+                        None,
+                        None,
+                    );
+                    continue;
+                }
+            };
+            match re.captures(&body) {
+                Some(caps) => match_captures.extend(named_captures(
+                    &re,
+                    &caps,
+                    names.as_deref(),
+                )),
+                None => {
+                    error!(
+                        "レスポンスボディが '{}' に一致しません \
+                         (ステップ: {})",
+                        pattern, step.name
+                    );
+                    matches_errors.push(format!(
+                        "matches: レスポンスボディが '{pattern}' に\
+                         一致しません"
+                    ));
+                }
+            }
+            log.record(
+                format!("matches {pattern}"),
+                &matches_errors,
+                before,
+                None,
+                None,
+            );
+        }
+        if !step.expect.matches.is_empty() {
+            let mut group = Vec::new();
+            if !matches_errors.is_empty() {
+                group.push(matches_errors.join("; "));
+            }
+            groups.push(group);
         }
 
         // SSE validation
@@ -870,15 +2407,26 @@ impl DefaultTestRunner {
                     step.name, err
                 );
             }
+            let mut sse_group: Vec<String> = Vec::new();
             if !sse_errors.is_empty() {
-                step_success = false;
-                step_error = Some(sse_errors.join("; "));
+                sse_group.push(sse_errors.join("; "));
             }
+            log.record_group("sse", &sse_group);
+            groups.push(sse_group);
             for (k, v) in sse_saved {
                 vars.insert(k, v);
             }
         }
 
+        if let Some(err) = Self::combine_expectation_groups(
+            step.expect.match_mode,
+            status_errors,
+            groups,
+        ) {
+            step_success = false;
+            step_error = Some(err);
+        }
+
         // ── CEL `test:` expression assertion ────────────
         if step_success {
             if let Some(ref test_expr) = step.test {
@@ -899,6 +2447,15 @@ impl DefaultTestRunner {
                             Some(format!("test expression error: {e}"));
                     }
                 }
+                let test_errors: Vec<String> =
+                    step_error.iter().cloned().collect();
+                log.record(
+                    format!("test {}", test_expr.trim()),
+                    &test_errors,
+                    0,
+                    None,
+                    None,
+                );
             }
         }
 
@@ -907,6 +2464,7 @@ impl DefaultTestRunner {
             if sse_events.is_some() {
                 let sse_value = &outputs_value;
                 for (var_name, path) in &step.save {
+                    let var_name = self.expand_variables(var_name, vars);
                     let actual_path =
                         if let Some(stripped) = path.strip_prefix("sse.") {
                             stripped
@@ -925,16 +2483,49 @@ impl DefaultTestRunner {
                         warn!("SSE save path '{}' not found", path);
                     }
                 }
-            } else if let Err(err) =
-                self.save_variables(&step.save, &body, vars).await
-            {
-                warn!("Failed to save variables: {}", err);
+            } else {
+                self.save_variables(
+                    &step.save, status, &headers, &body, vars,
+                );
+            }
+        }
+
+        // ── Save regex captures (muon native) ───────────
+        if step_success && !step.save_regex.is_empty() {
+            for (var_name, rule) in &step.save_regex {
+                let var_name = self.expand_variables(var_name, vars);
+                match extract_regex_capture(
+                    rule,
+                    &headers,
+                    &body,
+                    &parsed_json,
+                ) {
+                    Ok(val) => {
+                        debug!(
+                            "Saved regex variable '{}' = {:?}",
+                            var_name, val
+                        );
+                        vars.insert(var_name, Value::String(val));
+                    }
+                    Err(err) => {
+                        warn!("save_regex '{}' failed: {}", var_name, err);
+                    }
+                }
+            }
+        }
+
+        // ── Save `expect.matches` captures ──────────────
+        if step_success {
+            for (var_name, val) in match_captures {
+                debug!("Saved match variable '{}' = {:?}", var_name, val);
+                vars.insert(var_name, Value::String(val));
             }
         }
 
         // ── Bind variables (runn-compatible, CEL) ───────
         if step_success && !step.bind.is_empty() {
             for (var_name, expr) in &step.bind {
+                let var_name = self.expand_variables(var_name, vars);
                 let expanded = self.expand_variables(expr, vars);
                 match expression::resolve_value(&expanded, vars) {
                     Ok(val) => {
@@ -980,12 +2571,6 @@ impl DefaultTestRunner {
         // Update `previous` for next step
         *previous_value = Some(current_value);
 
-        // Add env variables
-        for (key, value) in std::env::vars() {
-            vars.entry(format!("env.{key}"))
-                .or_insert_with(|| Value::String(value));
-        }
-
         Ok(Some(StepResult {
             name: step.name.clone(),
             success: step_success,
@@ -993,71 +2578,785 @@ impl DefaultTestRunner {
             request: req_info,
             response: response_info,
             duration_ms,
+            vars_snapshot: self.vars_snapshot(step_success, vars),
+            skipped: false,
+            skip_reason: None,
+            assertions: log.entries,
+            // Under `match: any` a passing step may still have
+            // mismatches in the groups that did not pass
+            diff: if step_success { Vec::new() } else { diff },
+            attempts: 1,
         }))
     }
-}
 
-#[async_trait]
-impl TestRunner for DefaultTestRunner {
-    #[instrument(skip(self, scenario), fields(name = %scenario.name))]
-    async fn run(&self, scenario: &TestScenario) -> Result<TestResult> {
-        let start_time = Instant::now();
-        let mut scenario_success = true;
-        let mut step_results = Vec::new();
-        let mut vars = scenario.vars.clone();
-        let mut steps_map: Map<String, Value> = Map::new();
-        let mut step_key_counts: HashMap<String, usize> = HashMap::new();
-        let mut previous_value: Option<Value> = None;
+    /// Run `setup`, then (if it passed) the main steps and the
+    /// scenario assertion. Returns the scenario's success and
+    /// error before teardown.
+    async fn run_setup_and_steps(
+        &self,
+        scenario: &TestScenario,
+        state: &mut StepRunState,
+    ) -> Result<(bool, Option<String>)> {
+        if !self
+            .run_steps(scenario, &scenario.setup, "setup", true, state)
+            .await?
+        {
+            error!("Scenario '{}': setup failed", scenario.name);
+            return Ok((false, Some("setup failed".to_string())));
+        }
 
-        info!("Starting test scenario: {}", scenario.name);
+        let stop_on_failure = !scenario.config.continue_on_failure;
+        if !self
+            .run_steps(
+                scenario,
+                &scenario.steps,
+                "step",
+                stop_on_failure,
+                state,
+            )
+            .await?
+        {
+            return Ok((
+                false,
+                Some("一部のステップが失敗しました".to_string()),
+            ));
+        }
+
+        // Scenario-level assertion over the whole flow
+        if let (false, Some(assert_expr)) = (self.dry_run, &scenario.assert)
+        {
+            if let Err(e) = check_scenario_assert(assert_expr, &state.vars)
+            {
+                error!("Scenario '{}' assertion: {}", scenario.name, e);
+                return Ok((false, Some(e)));
+            }
+        }
+        Ok((true, None))
+    }
 
-        for (step_idx, step) in scenario.steps.iter().enumerate() {
+    /// Run `steps` in order, recording their results in `state`.
+    /// Returns `false` if any step failed; with `stop_on_failure`
+    /// the remaining steps are not run after the first failure.
+    async fn run_steps(
+        &self,
+        scenario: &TestScenario,
+        steps: &[TestStep],
+        phase: &str,
+        stop_on_failure: bool,
+        state: &mut StepRunState,
+    ) -> Result<bool> {
+        let mut all_passed = true;
+        for (step_idx, step) in steps.iter().enumerate() {
             info!(
-                "Running step {}/{}: {}",
+                "Running {} {}/{}: {}",
+                phase,
                 step_idx + 1,
-                scenario.steps.len(),
+                steps.len(),
                 step.name
             );
 
+            let shadowed = push_step_vars(&mut state.vars, &step.vars);
             let result = self
-                .execute_step_with_loop(
+                .execute_step_with_retry(
                     step,
-                    &mut vars,
+                    &mut state.vars,
                     &scenario.config,
-                    &mut steps_map,
+                    &mut state.steps_map,
                     step_idx,
-                    &mut step_key_counts,
-                    &mut previous_value,
+                    &mut state.step_key_counts,
+                    &mut state.previous_value,
                 )
                 .await?;
+            pop_step_vars(&mut state.vars, &step.vars, shadowed);
 
-            if let Some(step_result) = result {
-                let failed = !step_result.success;
-                step_results.push(step_result);
-
-                if failed {
-                    scenario_success = false;
-                    if !scenario.config.continue_on_failure {
-                        info!(
-                            "Stopping scenario due to step \
-                             failure"
-                        );
-                        break;
-                    }
+            let Some(mut step_result) = result else {
+                continue;
+            };
+            let failed = !step_result.success;
+            if state.any_failed && !self.dump_vars {
+                // Only the first failing step keeps its snapshot
+                step_result.vars_snapshot = None;
+            }
+            if let Some(observer) = &self.observer {
+                observer.on_step(scenario, &step_result);
+            }
+            state.step_results.push(step_result);
+
+            if failed {
+                all_passed = false;
+                state.any_failed = true;
+                if stop_on_failure {
+                    info!("Stopping scenario due to step failure");
+                    break;
                 }
             }
         }
+        Ok(all_passed)
+    }
+}
+
+/// Variables and step bookkeeping shared by the setup, main and
+/// teardown phases of one scenario run.
+#[derive(Default)]
+struct StepRunState {
+    vars: HashMap<String, Value>,
+    steps_map: Map<String, Value>,
+    step_key_counts: HashMap<String, usize>,
+    previous_value: Option<Value>,
+    step_results: Vec<StepResult>,
+    /// Whether a step already failed (and kept the vars snapshot).
+    any_failed: bool,
+}
+
+/// Per-assertion outcomes collected for
+/// [`DefaultTestRunner::with_explain`]; recording is a no-op while
+/// disabled.
+struct AssertionLog {
+    enabled: bool,
+    entries: Vec<AssertionResult>,
+}
+
+impl AssertionLog {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Record one check whose failures are the entries of
+    /// `errors` pushed after index `before`.
+    fn record(
+        &mut self,
+        check: impl Into<String>,
+        errors: &[String],
+        before: usize,
+        expected: Option<String>,
+        actual: Option<String>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let failures = &errors[before.min(errors.len())..];
+        self.entries.push(AssertionResult {
+            check: check.into(),
+            passed: failures.is_empty(),
+            expected,
+            actual,
+            error: (!failures.is_empty()).then(|| failures.join("; ")),
+        });
+    }
+
+    /// Record a whole expectation group as one check.
+    fn record_group(&mut self, check: &str, errors: &[String]) {
+        self.record(check, errors, 0, None, None);
+    }
+}
+
+/// Return `true` for variables that hold runner machinery rather
+/// than values produced by the scenario.
+fn is_internal_var(key: &str) -> bool {
+    matches!(
+        key,
+        "steps" | "current" | "previous" | "env" | LOOP_INDEX_VAR
+    ) || key.starts_with("steps.")
+        || key.starts_with("env.")
+}
+
+/// Value of a special `save` path that binds the whole response:
+/// `__status__` (number), `__body__` (raw text) or `__headers__`
+/// (object). `None` for any other path.
+fn special_save_value(
+    path: &str,
+    status: u16,
+    headers: &HashMap<String, String>,
+    body: &str,
+) -> Option<Value> {
+    match path {
+        "__status__" => Some(Value::from(status)),
+        "__body__" => Some(Value::String(body.to_string())),
+        "__headers__" => Some(
+            headers
+                .iter()
+                .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+                .collect::<Map<_, _>>()
+                .into(),
+        ),
+        _ => None,
+    }
+}
+
+/// Header name of a `header.<Name>` source path, as used by `save`
+/// and `save_regex.from`. `headers.<Name>` is accepted as well so
+/// either spelling works in both places.
+fn response_header_name(path: &str) -> Option<&str> {
+    path.strip_prefix("header.")
+        .or_else(|| path.strip_prefix("headers."))
+}
+
+/// Prefix a relative request URL with `base_path`, normalizing the
+/// slash between them.
+fn join_base_path(base_path: &str, url: &str) -> String {
+    let prefix = base_path.trim_end_matches('/');
+    let rest = url.trim_start_matches('/');
+    if rest.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{prefix}/{rest}")
+    }
+}
+
+/// Evaluate a scenario-level `assert` expression.
+///
+/// The context holds the final runner variables, plus a `vars`
+/// map of the user variables for explicit references.
+fn check_scenario_assert(
+    expr: &str,
+    vars: &HashMap<String, Value>,
+) -> std::result::Result<(), String> {
+    let mut context = vars.clone();
+    if !context.contains_key("vars") {
+        let outputs = collect_outputs(vars).into_iter().collect();
+        context.insert("vars".to_string(), Value::Object(outputs));
+    }
+    match expression::evaluate_test(expr, &context) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("scenario assert failed: {expr}")),
+        Err(e) => Err(format!("scenario assert error: {e}")),
+    }
+}
+
+/// Compare an expected HTTP version label against the negotiated
+/// one, treating `HTTP/2` as `HTTP/2.0`.
+fn http_version_matches(expected: &str, actual: &str) -> bool {
+    let normalize = |v: &str| {
+        let v = v.trim().to_ascii_uppercase();
+        if v.contains('.') {
+            v
+        } else {
+            format!("{v}.0")
+        }
+    };
+    normalize(expected) == normalize(actual)
+}
+
+/// Expand `{{ key }}` or `{{ vars.key }}` placeholders in `text`
+/// by looking up each key in `vars`. A dotted key without a flat
+/// entry is resolved inside structured variables, e.g.
+/// `{{ previous.res.body.id }}` (see [`lookup_nested`]).
+///
+/// Unknown keys are left as written. Expansion is a single pass:
+/// a substituted value that itself contains `{{ ... }}` is
+/// inserted verbatim and never expanded again. A backslash
+/// before the braces (`\{{ key }}`) escapes the placeholder; the
+/// backslash is dropped and the rest kept literally.
+///
+/// `{{ env.KEY }}` reads the environment variable `KEY` when the
+/// text is expanded, falling back to `vars`; an unset variable
+/// leaves the placeholder as written.
+fn expand_placeholders(
+    text: &str,
+    vars: &HashMap<String, Value>,
+) -> String {
+    use std::sync::LazyLock;
+
+    // Matches an optional escaping backslash (group 1) and
+    // {{ key }} or {{ vars.key }}, capturing the key (group 2).
+    static PLACEHOLDER_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(\\)?\{\{\s*(?:vars\.)?(.+?)\s*\}\}")
+            .expect("failed to compile placeholder regex")
+    });
+
+    PLACEHOLDER_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            if caps.get(1).is_some() {
+                return caps[0][1..].to_string();
+            }
+            let key = &caps[2];
+            if let Some(value) = key
+                .strip_prefix("env.")
+                .and_then(|name| std::env::var(name).ok())
+            {
+                return value;
+            }
+            match vars.get(key).or_else(|| lookup_nested(key, vars)) {
+                Some(Value::String(s)) => s.clone(),
+                Some(v) => v.to_string(),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// One-line summary of a loop iteration's result for the log:
+/// the response status and whether the step passed.
+fn iteration_outcome(result: &StepResult) -> String {
+    if result.skipped {
+        return "skipped".to_string();
+    }
+    let status = result.response.as_ref().map_or_else(
+        || "no response".to_string(),
+        |r| r.status.to_string(),
+    );
+    match (&result.error, result.success) {
+        (_, true) => format!("status {status}, passed"),
+        (Some(error), false) => format!("status {status}, failed: {error}"),
+        (None, false) => format!("status {status}, failed"),
+    }
+}
+
+/// Resolve a dotted `key` such as `previous.res.body.id` inside a
+/// structured variable (`steps`, `current`, `previous`, or an
+/// object from `vars`) that has no flattened entry. The longest
+/// variable name that prefixes `key` wins.
+fn lookup_nested<'a>(
+    key: &str,
+    vars: &'a HashMap<String, Value>,
+) -> Option<&'a Value> {
+    key.rmatch_indices('.').find_map(|(i, _)| {
+        vars.get(&key[..i]).and_then(|base| {
+            DefaultTestRunner::get_value_by_path(base, &key[i + 1..])
+        })
+    })
+}
+
+/// Check the limit and remaining-quota headers named in
+/// `expect` against its expected values.
+fn check_rate_limit(
+    expect: &RateLimitExpectation,
+    headers: &HashMap<String, String>,
+) -> Vec<String> {
+    let header_number = |name: &str| -> Result<u64, String> {
+        let value = headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+            .ok_or_else(|| {
+                format!(
+                    "rate_limit: ヘッダー '{name}' が\
+                     レスポンスに存在しません"
+                )
+            })?;
+        value.trim().parse().map_err(|_| {
+            format!(
+                "rate_limit: ヘッダー '{name}' の値が数値ではありません: \
+                 {value}"
+            )
+        })
+    };
+
+    let mut errors = Vec::new();
+    if let Some(expected) = expect.limit {
+        match header_number(&expect.limit_header) {
+            Ok(actual) if actual != expected => errors.push(format!(
+                "rate_limit: ヘッダー '{}' の値が期待値と一致しません。\
+                 期待: {expected}, 実際: {actual}",
+                expect.limit_header
+            )),
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+    if let Some(min) = expect.remaining_min {
+        match header_number(&expect.remaining_header) {
+            Ok(actual) if actual < min => errors.push(format!(
+                "rate_limit: ヘッダー '{}' の値が下限を下回っています。\
+                 期待: {min} 以上, 実際: {actual}",
+                expect.remaining_header
+            )),
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+    errors
+}
+
+/// Check one `json_approx` entry against the value found at
+/// `path`, returning an error message on mismatch.
+fn check_json_approx(
+    actual: Option<&Value>,
+    path: &str,
+    approx: &ApproxExpectation,
+) -> Option<String> {
+    let actual = match actual {
+        Some(Value::Number(n)) => n.as_f64()?,
+        Some(other) => {
+            return Some(format!(
+                "json_approx: JSONパス '{path}' の値が\
+                 数値ではありません (実際: {other})"
+            ))
+        }
+        None => {
+            return Some(format!(
+                "json_approx: JSONパス '{path}' が\
+                 レスポンスに存在しません"
+            ))
+        }
+    };
+    let diff = (actual - approx.value).abs();
+    (diff > approx.epsilon).then(|| {
+        format!(
+            "json_approx: JSONパス '{path}' の値が許容範囲外です。\
+             期待: {} ± {}, 実際: {actual} (差: {diff})",
+            approx.value, approx.epsilon
+        )
+    })
+}
+
+/// Look up the variable when `text` is exactly one placeholder
+/// such as `{{ key }}` or `{{ vars.key }}`, including dotted keys
+/// inside structured variables (see [`lookup_nested`]).
+fn whole_placeholder_value<'a>(
+    text: &str,
+    vars: &'a HashMap<String, Value>,
+) -> Option<&'a Value> {
+    let inner = text.trim().strip_prefix("{{")?.strip_suffix("}}")?.trim();
+    if inner.contains("{{") || inner.contains("}}") {
+        return None;
+    }
+    let key = inner.strip_prefix("vars.").unwrap_or(inner);
+    vars.get(key).or_else(|| lookup_nested(key, vars))
+}
+
+/// A parsed `expect.contains` entry.
+enum ContainsEntry<'a> {
+    Literal(&'a str),
+    Regex(&'a str),
+}
+
+/// Classify a `contains` entry: a `re:` prefix marks a regex and
+/// a leading backslash escapes a literal that starts with `re:`.
+fn contains_entry(entry: &str) -> ContainsEntry<'_> {
+    if let Some(pattern) = entry.strip_prefix("re:") {
+        ContainsEntry::Regex(pattern)
+    } else if entry.starts_with("\\re:") {
+        ContainsEntry::Literal(&entry[1..])
+    } else {
+        ContainsEntry::Literal(entry)
+    }
+}
+
+/// Collect the named groups of a `matches` regex that took part
+/// in the match, limited to `names` when given.
+fn named_captures(
+    re: &Regex,
+    caps: &regex::Captures<'_>,
+    names: Option<&[String]>,
+) -> Vec<(String, String)> {
+    re.capture_names()
+        .flatten()
+        .filter(|name| {
+            names.is_none_or(|names| names.iter().any(|n| n == name))
+        })
+        .filter_map(|name| {
+            caps.name(name)
+                .map(|m| (name.to_string(), m.as_str().to_string()))
+        })
+        .collect()
+}
+
+/// Resolve the source of a [`SaveRegex`] rule and return its first
+/// capture group.
+fn extract_regex_capture(
+    rule: &SaveRegex,
+    headers: &HashMap<String, String>,
+    body: &str,
+    parsed_json: &Option<Value>,
+) -> Result<String> {
+    let source = if let Some(name) = response_header_name(&rule.from) {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.clone())
+            .ok_or_else(|| {
+                anyhow!("header '{name}' not found in response")
+            })?
+    } else if rule.from == "body" {
+        body.to_string()
+    } else {
+        let json = parsed_json
+            .as_ref()
+            .ok_or_else(|| anyhow!("response is not valid JSON"))?;
+        match DefaultTestRunner::get_value_by_path(json, &rule.from) {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => {
+                return Err(anyhow!(
+                    "JSON path '{}' not found in response",
+                    rule.from
+                ))
+            }
+        }
+    };
+
+    let re = Regex::new(&rule.pattern)
+        .context(format!("invalid regex '{}'", rule.pattern))?;
+    let captures = re.captures(&source).ok_or_else(|| {
+        anyhow!("pattern '{}' did not match '{source}'", rule.pattern)
+    })?;
+    captures
+        .get(1)
+        .map(|m| m.as_str().to_string())
+        .ok_or_else(|| {
+            anyhow!("pattern '{}' has no capture group", rule.pattern)
+        })
+}
+
+/// Layer a step's `vars` over `vars`, returning the values they
+/// shadow so [`pop_step_vars`] can restore them.
+fn push_step_vars(
+    vars: &mut HashMap<String, Value>,
+    step_vars: &HashMap<String, Value>,
+) -> HashMap<String, Option<Value>> {
+    step_vars
+        .iter()
+        .map(|(key, value)| {
+            (key.clone(), vars.insert(key.clone(), value.clone()))
+        })
+        .collect()
+}
+
+/// Remove a step's `vars` again and restore shadowed values.
+/// Keys reassigned by the step itself (via `save`/`bind`) keep
+/// their new value.
+fn pop_step_vars(
+    vars: &mut HashMap<String, Value>,
+    step_vars: &HashMap<String, Value>,
+    shadowed: HashMap<String, Option<Value>>,
+) {
+    for (key, previous) in shadowed {
+        if vars.get(&key) != step_vars.get(&key) {
+            continue;
+        }
+        match previous {
+            Some(value) => {
+                vars.insert(key, value);
+            }
+            None => {
+                vars.remove(&key);
+            }
+        }
+    }
+}
+
+/// Encode a flat JSON object as `application/x-www-form-urlencoded`.
+/// String values are sent as-is; other scalars use their JSON text.
+fn encode_form_body(body: &Value) -> Result<String> {
+    let map = body
+        .as_object()
+        .ok_or_else(|| anyhow!("form body must be an object"))?;
+    let mut pairs: Vec<(&str, String)> = Vec::with_capacity(map.len());
+    for (key, value) in map {
+        pairs.push((key, form_field_text(key, value)?));
+    }
+    serde_urlencoded::to_string(&pairs)
+        .context("Failed to encode form body")
+}
+
+/// Text of a scalar form field.
+fn form_field_text(key: &str, value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Null => Ok(String::new()),
+        Value::Bool(_) | Value::Number(_) => Ok(value.to_string()),
+        _ => Err(anyhow!("form field '{key}' must be a scalar value")),
+    }
+}
+
+/// Build a multipart form from a JSON object. A value of the form
+/// `{ "file": "path", "content_type": "..." }` becomes a file part
+/// read from disk; scalars become text parts.
+fn build_multipart_form(body: &Value) -> Result<multipart::Form> {
+    let map = body
+        .as_object()
+        .ok_or_else(|| anyhow!("multipart body must be an object"))?;
+    let mut form = multipart::Form::new();
+    for (key, value) in map {
+        if let Some(path) = value.get("file").and_then(Value::as_str) {
+            let bytes = std::fs::read(path).context(format!(
+                "Failed to read multipart file for '{key}': {path}"
+            ))?;
+            let file_name = std::path::Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string());
+            let mut part =
+                multipart::Part::bytes(bytes).file_name(file_name);
+            if let Some(mime) =
+                value.get("content_type").and_then(Value::as_str)
+            {
+                part = part.mime_str(mime)?;
+            }
+            form = form.part(key.clone(), part);
+        } else {
+            form = form.text(key.clone(), form_field_text(key, value)?);
+        }
+    }
+    Ok(form)
+}
+
+/// Compress a request body with the given algorithm.
+fn compress_body(compression: Compression, body: &str) -> Result<Vec<u8>> {
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use std::io::Write;
+
+    let level = flate2::Compression::default();
+    let compressed = match compression {
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), level);
+            encoder.write_all(body.as_bytes())?;
+            encoder.finish()?
+        }
+        Compression::Deflate => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), level);
+            encoder.write_all(body.as_bytes())?;
+            encoder.finish()?
+        }
+    };
+    Ok(compressed)
+}
+
+/// Nearest-rank percentile of `sorted` (ascending, non-empty).
+pub(crate) fn percentile(sorted: &[u64], pct: f64) -> u64 {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Validate iteration durations against `timing`, returning one
+/// error per exceeded percentile.
+fn check_loop_timing(
+    timing: &LoopTimingExpectation,
+    durations: &[u64],
+) -> Vec<String> {
+    if durations.is_empty() {
+        return vec![];
+    }
+    let mut sorted = durations.to_vec();
+    sorted.sort_unstable();
+
+    [
+        ("p50", 50.0, timing.p50_ms),
+        ("p90", 90.0, timing.p90_ms),
+        ("p95", 95.0, timing.p95_ms),
+        ("p99", 99.0, timing.p99_ms),
+    ]
+    .into_iter()
+    .filter_map(|(label, pct, limit)| {
+        let limit = limit?;
+        let actual = percentile(&sorted, pct);
+        (actual > limit).then(|| {
+            format!(
+                "loop_timing: {label} {actual}ms が上限 {limit}ms を\
+                 超えました ({} 回の反復)",
+                sorted.len()
+            )
+        })
+    })
+    .collect()
+}
+
+/// Variable holding the ID injected via
+/// [`TestConfig::inject_request_id`] for the current step.
+const REQUEST_ID_VAR: &str = "request_id";
+
+/// Variable holding the zero-based iteration index of a looped
+/// step.
+const LOOP_INDEX_VAR: &str = "i";
+
+/// Collect the user-facing variables exposed as
+/// [`TestResult::outputs`].
+fn collect_outputs(
+    vars: &HashMap<String, Value>,
+) -> HashMap<String, Value> {
+    vars.iter()
+        .filter(|(key, _)| !is_internal_var(key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+#[async_trait]
+impl TestRunner for DefaultTestRunner {
+    #[instrument(skip(self, scenario), fields(name = %scenario.name))]
+    async fn run(&self, scenario: &TestScenario) -> Result<TestResult> {
+        let start_time = Instant::now();
+        let mut vars = scenario.vars.clone();
+        // Expose the process environment from step 1: flat
+        // `env.KEY` entries for placeholders and an `env` map for
+        // `test:` expressions
+        let mut env_map = Map::new();
+        for (key, value) in std::env::vars() {
+            vars.entry(format!("env.{key}"))
+                .or_insert_with(|| Value::String(value.clone()));
+            env_map.insert(key, Value::String(value));
+        }
+        vars.entry("env".to_string())
+            .or_insert(Value::Object(env_map));
+        info!("Starting test scenario: {}", scenario.name);
+        if scenario.config.insecure_tls {
+            warn!(
+                "insecure_tls is enabled for '{}': TLS certificates are \
+                 NOT verified",
+                scenario.name
+            );
+        }
+
+        let missing_env: Vec<&str> = scenario
+            .requires_env
+            .iter()
+            .filter(|key| {
+                std::env::var(key.as_str()).map_or(true, |v| v.is_empty())
+            })
+            .map(|key| key.as_str())
+            .collect();
+        if !missing_env.is_empty() {
+            let error =
+                format!("missing required env: {}", missing_env.join(", "));
+            error!("Scenario '{}' aborted: {}", scenario.name, error);
+            return Ok(TestResult {
+                name: scenario.name.clone(),
+                success: false,
+                error: Some(error),
+                steps: vec![],
+                duration_ms: start_time.elapsed().as_millis() as u64,
+                outputs: HashMap::new(),
+            });
+        }
+
+        let mut state = StepRunState {
+            vars,
+            ..Default::default()
+        };
+        let main = self.run_setup_and_steps(scenario, &mut state).await;
+
+        // Teardown always runs, even after a failure or error above
+        let teardown_ok = self
+            .run_steps(
+                scenario,
+                &scenario.teardown,
+                "teardown",
+                false,
+                &mut state,
+            )
+            .await;
+        let (mut scenario_success, mut scenario_error) = main?;
+        if !teardown_ok? {
+            warn!("Scenario '{}': teardown failed", scenario.name);
+            if scenario.config.fail_on_teardown && scenario_success {
+                scenario_success = false;
+                scenario_error = Some("teardown failed".to_string());
+            }
+        }
+        let StepRunState {
+            vars, step_results, ..
+        } = state;
 
         let result = TestResult {
             name: scenario.name.clone(),
             success: scenario_success,
-            error: if scenario_success {
-                None
-            } else {
-                Some("一部のステップが失敗しました".to_string())
-            },
+            error: scenario_error,
             steps: step_results,
             duration_ms: start_time.elapsed().as_millis() as u64,
+            outputs: collect_outputs(&vars),
         };
 
         info!(
@@ -1068,3 +3367,300 @@ impl TestRunner for DefaultTestRunner {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vars(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_build_current_value_resolves_url_and_query() {
+        let req_info = RequestInfo {
+            method: "Get".into(),
+            url: "http://localhost/api/users/42?page=2&sort=id".into(),
+            headers: HashMap::new(),
+            query: HashMap::from([
+                ("sort".to_string(), "name".to_string()),
+                ("limit".to_string(), "10".to_string()),
+            ]),
+            body: None,
+        };
+        let current = DefaultTestRunner::build_current_value(
+            200,
+            &HashMap::new(),
+            &None,
+            "",
+            &req_info,
+            0,
+        );
+
+        let req = &current["req"];
+        assert_eq!(
+            req["query"],
+            json!({"page": "2", "sort": "name", "limit": "10"})
+        );
+        assert_eq!(
+            req["resolvedUrl"],
+            "http://localhost/api/users/42?page=2&sort=id&limit=10&sort=name"
+        );
+        assert_eq!(req["path"], "/api/users/42");
+        assert_eq!(req["pathSegments"], json!(["api", "users", "42"]));
+        assert_eq!(
+            req["url"],
+            "http://localhost/api/users/42?page=2&sort=id"
+        );
+    }
+
+    #[test]
+    fn test_expand_placeholders_basic() {
+        let vars = vars(&[("name", json!("alice")), ("n", json!(3))]);
+        assert_eq!(
+            expand_placeholders(
+                "{{ name }}/{{vars.n}}/{{ missing }}",
+                &vars
+            ),
+            "alice/3/{{ missing }}"
+        );
+    }
+
+    #[test]
+    fn test_expand_placeholders_nested_paths() {
+        let vars = vars(&[
+            ("steps", json!({"login": {"response": {"json": {"id": 7}}}})),
+            (
+                "previous",
+                json!({"res": {"body": {"items": [{"id": "a"}]}}}),
+            ),
+            ("user", json!({"name": "alice"})),
+            ("user.name", json!("flat")),
+        ]);
+        assert_eq!(
+            expand_placeholders(
+                "/users/{{ steps.login.response.json.id }}/items/\
+                 {{ previous.res.body.items.0.id }}",
+                &vars
+            ),
+            "/users/7/items/a"
+        );
+        // Flat keys take precedence over nested lookups
+        assert_eq!(expand_placeholders("{{ user.name }}", &vars), "flat");
+        assert_eq!(
+            expand_placeholders("{{ previous.res.body.missing }}", &vars),
+            "{{ previous.res.body.missing }}"
+        );
+    }
+
+    #[test]
+    fn test_expand_placeholders_escape() {
+        let vars = vars(&[("name", json!("alice"))]);
+        assert_eq!(
+            expand_placeholders(r"\{{ name }} is {{ name }}", &vars),
+            "{{ name }} is alice"
+        );
+        assert_eq!(
+            expand_placeholders(
+                r"\{{#each items}}\{{this}}\{{/each}}",
+                &vars
+            ),
+            "{{#each items}}{{this}}{{/each}}"
+        );
+    }
+
+    #[test]
+    fn test_expand_placeholders_env() {
+        std::env::set_var("MUON_TEST_EXPAND_ENV", "from-env");
+        let vars =
+            vars(&[("env.MUON_TEST_EXPAND_VAR", json!("from-vars"))]);
+        assert_eq!(
+            expand_placeholders("{{ env.MUON_TEST_EXPAND_ENV }}", &vars),
+            "from-env"
+        );
+        assert_eq!(
+            expand_placeholders("{{ env.MUON_TEST_EXPAND_VAR }}", &vars),
+            "from-vars"
+        );
+        assert_eq!(
+            expand_placeholders("{{ env.MUON_TEST_EXPAND_UNSET }}", &vars),
+            "{{ env.MUON_TEST_EXPAND_UNSET }}"
+        );
+    }
+
+    #[test]
+    fn test_expand_placeholders_does_not_reexpand_values() {
+        let vars = vars(&[
+            ("template", json!("Hello {{ name }}")),
+            ("name", json!("alice")),
+        ]);
+        assert_eq!(
+            expand_placeholders("{{ template }}", &vars),
+            "Hello {{ name }}"
+        );
+    }
+
+    #[test]
+    fn test_expand_json_value_keeps_variable_type() {
+        let runner = DefaultTestRunner::new();
+        let initial = vars(&[("id", json!(42)), ("region", json!("eu"))]);
+        assert_eq!(
+            runner.expand_json_value(
+                &json!([
+                    "{{ id }}",
+                    "{{ vars.id }}",
+                    "id-{{ id }}",
+                    "{{ x }}"
+                ]),
+                &initial
+            ),
+            json!([42, 42, "id-42", "{{ x }}"])
+        );
+        assert_eq!(
+            whole_placeholder_value("{{ region }}", &initial),
+            Some(&json!("eu"))
+        );
+        assert_eq!(
+            whole_placeholder_value("{{ a }}{{ b }}", &initial),
+            None
+        );
+        let vars =
+            vars(&[("previous", json!({"res": {"body": {"n": 1}}}))]);
+        assert_eq!(
+            whole_placeholder_value("{{ previous.res.body }}", &vars),
+            Some(&json!({"n": 1}))
+        );
+    }
+
+    #[test]
+    fn test_check_rate_limit() {
+        let headers: HashMap<String, String> = [
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "3"),
+            ("ratelimit-policy", "soon"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let expect = |limit, remaining_min, limit_header: &str| {
+            RateLimitExpectation {
+                limit,
+                remaining_min,
+                limit_header: limit_header.to_string(),
+                remaining_header: "X-RateLimit-Remaining".to_string(),
+            }
+        };
+
+        assert!(check_rate_limit(
+            &expect(Some(100), Some(3), "X-RateLimit-Limit"),
+            &headers
+        )
+        .is_empty());
+
+        let errors = check_rate_limit(
+            &expect(Some(50), Some(10), "X-RateLimit-Limit"),
+            &headers,
+        );
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors[0].contains("実際: 100"));
+        assert!(errors[1].contains("期待: 10 以上"));
+
+        let errors = check_rate_limit(
+            &expect(Some(1), None, "RateLimit-Limit"),
+            &headers,
+        );
+        assert!(errors[0]
+            .contains("'RateLimit-Limit' がレスポンスに存在しません"));
+        let errors = check_rate_limit(
+            &expect(Some(1), None, "RateLimit-Policy"),
+            &headers,
+        );
+        assert!(errors[0].contains("数値ではありません"), "{errors:?}");
+    }
+
+    #[test]
+    fn test_wildcard_path_over_mixed_array() {
+        let body = json!({"items": [
+            {"id": 1, "active": true},
+            "scalar",
+            null,
+            {"id": [2, 3], "active": true},
+        ]});
+
+        let ids = DefaultTestRunner::get_values_by_wildcard_path(
+            &body,
+            "items.*.id",
+        )
+        .unwrap();
+        assert_eq!(
+            ids,
+            [
+                ("items.0.id".to_string(), Some(&json!(1))),
+                ("items.1.id".to_string(), None),
+                ("items.2.id".to_string(), None),
+                ("items.3.id".to_string(), Some(&json!([2, 3]))),
+            ]
+        );
+
+        let opts = crate::validator::CompareOptions::default();
+        let err = DefaultTestRunner::check_wildcard_json(
+            &body,
+            "items.*.active",
+            &json!(true),
+            opts,
+        )
+        .unwrap();
+        assert!(err.contains("'items.1.active'"), "{err}");
+        assert!(DefaultTestRunner::check_wildcard_json(
+            &json!({"items": [{"a": 1}, {"a": 1}]}),
+            "items.*.a",
+            &json!(1),
+            opts,
+        )
+        .is_none());
+        assert!(DefaultTestRunner::check_wildcard_json(
+            &body,
+            "missing.*.id",
+            &json!(1),
+            opts,
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn test_check_json_approx() {
+        let approx = ApproxExpectation {
+            value: 0.3,
+            epsilon: 1e-9,
+        };
+        assert!(check_json_approx(Some(&json!(0.1 + 0.2)), "x", &approx)
+            .is_none());
+        let err = check_json_approx(Some(&json!(0.31)), "x", &approx)
+            .expect("0.31 is outside the tolerance");
+        assert!(err.contains("期待: 0.3"), "{err}");
+        assert!(check_json_approx(Some(&json!("0.3")), "x", &approx)
+            .unwrap()
+            .contains("数値ではありません"));
+        assert!(check_json_approx(None, "x", &approx)
+            .unwrap()
+            .contains("存在しません"));
+    }
+
+    #[test]
+    fn test_expand_json_value_keeps_structure() {
+        let runner = DefaultTestRunner::new();
+        let vars =
+            vars(&[("quote", json!("say \"hi\"")), ("k", json!("key"))]);
+        let body = json!({
+            "{{ k }}": ["{{ quote }}", r"\{{ quote }}", 1],
+        });
+        assert_eq!(
+            runner.expand_json_value(&body, &vars),
+            json!({"key": ["say \"hi\"", "{{ quote }}", 1]})
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! Soak runs: execute randomly chosen scenarios in a loop for a
+//! fixed duration and aggregate the outcomes.
+
+use crate::model::{LatencyStats, TestScenario};
+use crate::suite::{latency_from_durations, run_one, RunOptions};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+/// Aggregated outcome of [`run_soak`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SoakReport {
+    /// Seed used for scenario selection.
+    pub seed: u64,
+    /// Wall-clock time of the whole soak run.
+    pub duration_ms: u64,
+    /// Number of scenario runs.
+    pub iterations: usize,
+    /// Runs that passed.
+    pub passed: usize,
+    /// Runs that failed.
+    pub failed: usize,
+    /// Per-scenario counts, keyed by scenario name.
+    pub scenarios: BTreeMap<String, SoakScenarioStats>,
+    /// Step latency across every run.
+    pub latency: Option<LatencyStats>,
+}
+
+/// Run counts for one scenario in a [`SoakReport`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SoakScenarioStats {
+    /// Runs that passed.
+    pub passed: usize,
+    /// Runs that failed.
+    pub failed: usize,
+}
+
+/// Outcome of a single scenario run inside a soak worker.
+struct Sample {
+    scenario: usize,
+    success: bool,
+    step_durations: Vec<u64>,
+}
+
+/// Repeatedly run randomly picked scenarios until `duration` has
+/// elapsed and return the aggregated report.
+///
+/// Scenarios are picked in proportion to [`TestScenario::weight`].
+/// `options.concurrency` workers run side by side, each with its
+/// own generator derived from `seed`, so the picks of every
+/// worker are reproducible. Runs still in flight at the deadline
+/// finish and are counted.
+pub async fn run_soak(
+    scenarios: Vec<TestScenario>,
+    options: RunOptions,
+    duration: Duration,
+    seed: u64,
+) -> Result<SoakReport> {
+    let start = Instant::now();
+    let deadline = start + duration;
+    let scenarios = options.prepare(scenarios)?;
+    let picker = Arc::new(WeightedPicker::new(&scenarios));
+    let scenarios = Arc::new(scenarios);
+    let runner = Arc::new(options.runner);
+    let workers = options.concurrency.max(1);
+    info!(
+        "Soaking {} scenario(s) for {:?} with {} worker(s), seed {}",
+        scenarios.len(),
+        duration,
+        workers,
+        seed
+    );
+
+    let mut tasks = JoinSet::new();
+    for worker in 0..workers {
+        let picker = Arc::clone(&picker);
+        let scenarios = Arc::clone(&scenarios);
+        let runner = Arc::clone(&runner);
+        tasks.spawn(async move {
+            let mut rng = SplitMix64(seed.wrapping_add(worker as u64));
+            let mut samples = Vec::new();
+            while Instant::now() < deadline {
+                let Some(idx) = picker.pick(&mut rng) else {
+                    break;
+                };
+                debug!(
+                    "Soak worker {} runs {}",
+                    worker, scenarios[idx].name
+                );
+                let result = run_one(&runner, &scenarios[idx]).await;
+                samples.push(Sample {
+                    scenario: idx,
+                    success: result.success,
+                    step_durations: result
+                        .steps
+                        .iter()
+                        .filter(|s| !s.skipped)
+                        .map(|s| s.duration_ms)
+                        .collect(),
+                });
+            }
+            samples
+        });
+    }
+
+    let mut report = SoakReport {
+        seed,
+        ..Default::default()
+    };
+    let mut durations = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let samples = match joined {
+            Ok(samples) => samples,
+            Err(e) => {
+                warn!("Soak worker panicked: {}", e);
+                continue;
+            }
+        };
+        for sample in samples {
+            let stats = report
+                .scenarios
+                .entry(scenarios[sample.scenario].name.clone())
+                .or_default();
+            if sample.success {
+                stats.passed += 1;
+                report.passed += 1;
+            } else {
+                stats.failed += 1;
+                report.failed += 1;
+            }
+            report.iterations += 1;
+            durations.extend(sample.step_durations);
+        }
+    }
+    report.latency = latency_from_durations(durations);
+    report.duration_ms = start.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// Parse a soak duration such as `90` (seconds), `500ms`, `30s`,
+/// `5m`, or `2h`.
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let value: u64 = number
+        .parse()
+        .context(format!("invalid duration '{text}'"))?;
+    let duration = match unit {
+        "ms" => Duration::from_millis(value),
+        "" | "s" => Duration::from_secs(value),
+        "m" => Duration::from_secs(value * 60),
+        "h" => Duration::from_secs(value * 3600),
+        _ => bail!(
+            "invalid duration unit '{unit}' (expected ms, s, m, or h)"
+        ),
+    };
+    Ok(duration)
+}
+
+/// Picks scenario indices in proportion to their weights.
+struct WeightedPicker {
+    /// Running total of weights; entry `i` covers scenario `i`.
+    cumulative: Vec<u64>,
+}
+
+impl WeightedPicker {
+    fn new(scenarios: &[TestScenario]) -> Self {
+        let mut total = 0;
+        let cumulative = scenarios
+            .iter()
+            .map(|s| {
+                total += u64::from(s.weight.unwrap_or(1));
+                total
+            })
+            .collect();
+        Self { cumulative }
+    }
+
+    /// Pick an index, or `None` when every weight is zero.
+    fn pick(&self, rng: &mut SplitMix64) -> Option<usize> {
+        let total = *self.cumulative.last()?;
+        if total == 0 {
+            return None;
+        }
+        let target = rng.next() % total;
+        Some(self.cumulative.partition_point(|&c| c <= target))
+    }
+}
+
+/// SplitMix64 generator used for reproducible scenario picks.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(weight: Option<u32>) -> TestScenario {
+        TestScenario::from_yaml("name: s\nsteps: []\n")
+            .map(|s| TestScenario { weight, ..s })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(
+            parse_duration("250ms").unwrap(),
+            Duration::from_millis(250)
+        );
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(
+            parse_duration("2h").unwrap(),
+            Duration::from_secs(7200)
+        );
+        assert!(parse_duration("5d").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn test_picker_follows_weights() {
+        let picker = WeightedPicker::new(&[
+            scenario(Some(3)),
+            scenario(Some(0)),
+            scenario(None),
+        ]);
+        let mut rng = SplitMix64(7);
+        let mut counts = [0usize; 3];
+        for _ in 0..4000 {
+            counts[picker.pick(&mut rng).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0, "zero weight is never picked");
+        assert!(
+            (2700..3300).contains(&counts[0]),
+            "weight 3 should get ~3/4 of picks: {counts:?}"
+        );
+    }
+
+    #[test]
+    fn test_picker_is_reproducible_and_handles_zero_weights() {
+        let scenarios = [scenario(None), scenario(Some(2))];
+        let picker = WeightedPicker::new(&scenarios);
+        let picks = |seed| {
+            let mut rng = SplitMix64(seed);
+            (0..20)
+                .map(|_| picker.pick(&mut rng).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(picks(42), picks(42));
+
+        let none = WeightedPicker::new(&[scenario(Some(0))]);
+        assert!(none.pick(&mut SplitMix64(1)).is_none());
+        assert!(WeightedPicker::new(&[])
+            .pick(&mut SplitMix64(1))
+            .is_none());
+    }
+}
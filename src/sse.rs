@@ -131,8 +131,8 @@ pub fn validate_sse(
     for required in &expect.has_events {
         if !event_types.contains(&required.as_str()) {
             errors.push(format!(
-                "SSE: expected event type '{required}' not found \
-                 (found: {event_types:?})"
+                "SSE: 期待するイベントタイプ '{required}' が\
+                 ストリームに存在しません (実際: {event_types:?})"
             ));
         }
     }
@@ -142,7 +142,7 @@ pub fn validate_sse(
     for forbidden in &expect.has_no_events {
         if event_types.contains(&forbidden.as_str()) {
             errors.push(format!(
-                "SSE: forbidden event type '{forbidden}' was found in stream"
+                "SSE: 禁止されたイベントタイプ '{forbidden}' がストリームに存在します"
             ));
         }
     }
@@ -165,8 +165,9 @@ pub fn validate_sse(
             expect.event_sequence.iter().map(|s| s.as_str()).collect();
         if actual != expected {
             errors.push(format!(
-                "SSE event_sequence mismatch — expected: {expected:?}, \
-                 actual (deduped): {actual:?} (raw: {actual_raw:?})"
+                "SSE event_sequence が期待値と一致しません。\
+                 期待: {expected:?}, 実際 (連続重複除去後): {actual:?} \
+                 (元の順序: {actual_raw:?})"
             ));
         }
     }
@@ -209,8 +210,8 @@ fn validate_ordered_events(
 
         if !found {
             errors.push(format!(
-                "SSE event[{exp_idx}]: expected event '{expanded_event}' not found \
-                 after scanning from position"
+                "SSE event[{exp_idx}]: 期待するイベント '{expanded_event}' が\
+                 現在位置以降に存在しません"
             ));
             continue;
         }
@@ -227,24 +228,24 @@ fn validate_ordered_events(
                         Some(actual) => {
                             if *actual != expanded_expected {
                                 errors.push(format!(
-                                    "SSE event[{exp_idx}] '{expanded_event}': field '{key}' \
-                                     mismatch — expected {expanded_expected:?}, \
-                                     got {actual:?}"
+                                    "SSE event[{exp_idx}] '{expanded_event}': フィールド '{key}' \
+                                     の値が期待値と一致しません。期待: {expanded_expected:?}, \
+                                     実際: {actual:?}"
                                 ));
                             }
                         }
                         None => {
                             errors.push(format!(
-                                "SSE event[{exp_idx}] '{expanded_event}': field '{key}' \
-                                 not found in data"
+                                "SSE event[{exp_idx}] '{expanded_event}': フィールド '{key}' \
+                                 がデータに存在しません"
                             ));
                         }
                     }
                 }
             } else {
                 errors.push(format!(
-                    "SSE event[{exp_idx}] '{expanded_event}': data is not valid JSON, \
-                     cannot check fields"
+                    "SSE event[{exp_idx}] '{expanded_event}': データが有効なJSONではないため\
+                     フィールドを検証できません"
                 ));
             }
         }
@@ -269,6 +270,7 @@ fn validate_ordered_events(
                     &expanded,
                     &exp.ignore_fields,
                     "",
+                    crate::validator::CompareOptions::default(),
                 );
                 for e in exact_errors {
                     errors.push(format!(
@@ -277,8 +279,8 @@ fn validate_ordered_events(
                 }
             } else {
                 errors.push(format!(
-                    "SSE event[{exp_idx}] '{expanded_event}': data is not valid \
-                     JSON, cannot run data_eq check"
+                    "SSE event[{exp_idx}] '{expanded_event}': データが有効なJSONではないため\
+                     data_eq を検証できません"
                 ));
             }
         }
@@ -288,7 +290,7 @@ fn validate_ordered_events(
             let expanded_substr = expand_fn(substr);
             if !event.data_raw.contains(&expanded_substr) {
                 errors.push(format!(
-                    "SSE event[{exp_idx}] '{expanded_event}': data does not contain '{expanded_substr}'"
+                    "SSE event[{exp_idx}] '{expanded_event}': データに '{expanded_substr}' が含まれていません"
                 ));
             }
         }
@@ -298,14 +300,14 @@ fn validate_ordered_events(
             if let Some(json) = &event.data_json {
                 if json.get(field).is_none() {
                     errors.push(format!(
-                        "SSE event[{exp_idx}] '{expanded_event}': expected field '{field}' \
-                         to exist in data"
+                        "SSE event[{exp_idx}] '{expanded_event}': フィールド '{field}' \
+                         がデータに存在しません"
                     ));
                 }
             } else {
                 errors.push(format!(
-                    "SSE event[{exp_idx}] '{expanded_event}': data is not valid JSON, \
-                     cannot check field existence for '{field}'"
+                    "SSE event[{exp_idx}] '{expanded_event}': データが有効なJSONではないため\
+                     フィールド '{field}' の存在を検証できません"
                 ));
             }
         }
@@ -317,8 +319,8 @@ fn validate_ordered_events(
                     saved_vars.insert(var_name.clone(), val.clone());
                 } else {
                     errors.push(format!(
-                        "SSE event[{exp_idx}] '{expanded_event}': save field '{data_field}' not \
-                         found in data"
+                        "SSE event[{exp_idx}] '{expanded_event}': save のフィールド '{data_field}' \
+                         がデータに存在しません"
                     ));
                 }
             }
@@ -507,7 +509,7 @@ mod tests {
         let identity = |s: &str| s.to_string();
         let (errors, _) = validate_sse(&events, &expect, &identity);
         assert_eq!(errors.len(), 1);
-        assert!(errors[0].contains("event_sequence mismatch"));
+        assert!(errors[0].contains("event_sequence が期待値と一致しません"));
     }
 
     #[test]
@@ -577,7 +579,7 @@ mod tests {
         let identity = |s: &str| s.to_string();
         let (errors, _) = validate_sse(&events, &expect, &identity);
         assert_eq!(errors.len(), 1);
-        assert!(errors[0].contains("forbidden"));
+        assert!(errors[0].contains("禁止されたイベントタイプ"));
     }
 
     #[test]
@@ -685,7 +687,7 @@ mod tests {
         let identity = |s: &str| s.to_string();
         let (errors, _) = validate_sse(&events, &expect, &identity);
         assert_eq!(errors.len(), 1);
-        assert!(errors[0].contains("mismatch"));
+        assert!(errors[0].contains("期待値と一致しません"));
     }
 
     #[test]
@@ -707,7 +709,7 @@ mod tests {
         let identity = |s: &str| s.to_string();
         let (errors, _) = validate_sse(&events, &expect, &identity);
         assert_eq!(errors.len(), 1);
-        assert!(errors[0].contains("does not contain"));
+        assert!(errors[0].contains("含まれていません"));
     }
 
     #[test]
@@ -729,6 +731,6 @@ mod tests {
         let identity = |s: &str| s.to_string();
         let (errors, _) = validate_sse(&events, &expect, &identity);
         assert_eq!(errors.len(), 1);
-        assert!(errors[0].contains("not found"));
+        assert!(errors[0].contains("存在しません"));
     }
 }
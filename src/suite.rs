@@ -0,0 +1,501 @@
+//! Programmatic suite runs: scenario discovery, CLI-style config
+//! overrides, execution, and aggregation into a [`TestRunReport`].
+
+use crate::config::TestConfigManager;
+use crate::model::{
+    CiMetadata, LatencyStats, TestConfig, TestResult, TestRunReport,
+    TestScenario,
+};
+use crate::runner::{percentile, DefaultTestRunner, TestRunner};
+use anyhow::{bail, Result};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+/// Options for [`run_suite`].
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    /// Only run scenarios whose name contains this text
+    /// (case-insensitive).
+    pub filter: Option<String>,
+    /// Per-step timeout override, in seconds.
+    pub timeout: Option<u64>,
+    /// Base URL override for every scenario.
+    pub base_url: Option<String>,
+    /// Path prefix override for relative request URLs.
+    pub base_path: Option<String>,
+    /// Maximum number of scenarios run at once; `0` and `1` run
+    /// sequentially.
+    pub concurrency: usize,
+    /// Stop at the first failing scenario: scenarios still running
+    /// are aborted and the remaining ones are not run.
+    pub fail_fast: bool,
+    /// Include step latency statistics in the report.
+    pub latency_stats: bool,
+    /// Environment whose `environments` vars are merged into
+    /// each scenario's `vars`.
+    pub environment: Option<String>,
+    /// Vars merged under each scenario's `vars`: the scenario's
+    /// own values win.
+    pub default_vars: HashMap<String, serde_json::Value>,
+    /// Vars merged over each scenario's `vars` (including the
+    /// selected environment's), replacing values of the same name.
+    pub override_vars: HashMap<String, serde_json::Value>,
+    /// Runner used for every scenario.
+    pub runner: DefaultTestRunner,
+}
+
+impl RunOptions {
+    /// Apply the timeout, base URL, and base path overrides to
+    /// `config`.
+    pub fn apply_overrides(&self, config: &mut TestConfig) {
+        if let Some(timeout) = self.timeout {
+            config.timeout = timeout;
+        }
+        if let Some(ref base_url) = self.base_url {
+            config.base_url = Some(base_url.clone());
+        }
+        if let Some(ref base_path) = self.base_path {
+            config.base_path = Some(base_path.clone());
+        }
+    }
+
+    /// Merge the selected environment's vars over
+    /// `scenario.vars`.
+    ///
+    /// Scenarios without `environments` are left unchanged; one
+    /// that defines environments but not the selected one is an
+    /// error, so it cannot silently run with the wrong expected
+    /// values.
+    pub fn apply_environment(
+        &self,
+        scenario: &mut TestScenario,
+    ) -> Result<()> {
+        let Some(ref name) = self.environment else {
+            return Ok(());
+        };
+        if scenario.environments.is_empty() {
+            return Ok(());
+        }
+        let Some(env_vars) = scenario.environments.get(name) else {
+            let mut known: Vec<&str> =
+                scenario.environments.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            bail!(
+                "scenario '{}' has no environment '{}' (defined: {})",
+                scenario.name,
+                name,
+                known.join(", ")
+            );
+        };
+        scenario.vars.extend(env_vars.clone());
+        Ok(())
+    }
+
+    /// Layer [`Self::default_vars`] under and
+    /// [`Self::override_vars`] over `scenario.vars`.
+    pub fn apply_vars(&self, scenario: &mut TestScenario) {
+        if !self.default_vars.is_empty() {
+            let own = std::mem::replace(
+                &mut scenario.vars,
+                self.default_vars.clone(),
+            );
+            scenario.vars.extend(own);
+        }
+        scenario.vars.extend(self.override_vars.clone());
+    }
+
+    /// Filter `scenarios` and apply the config overrides, the
+    /// selected environment, and the extra vars to the rest.
+    pub fn prepare(
+        &self,
+        scenarios: Vec<TestScenario>,
+    ) -> Result<Vec<TestScenario>> {
+        scenarios
+            .into_iter()
+            .filter(|s| self.matches_filter(s))
+            .map(|mut s| {
+                self.apply_overrides(&mut s.config);
+                self.apply_environment(&mut s)?;
+                self.apply_vars(&mut s);
+                Ok(s)
+            })
+            .collect()
+    }
+
+    /// Return `true` if `scenario` passes the name filter.
+    pub fn matches_filter(&self, scenario: &TestScenario) -> bool {
+        self.filter.as_ref().is_none_or(|filter| {
+            scenario
+                .name
+                .to_lowercase()
+                .contains(&filter.to_lowercase())
+        })
+    }
+}
+
+/// Parse `yaml` as a scenario and run it with a default
+/// [`DefaultTestRunner`], without touching the filesystem.
+///
+/// `base_url` overrides the scenario's `config.base_url`, like the
+/// CLI's `--base-url`.
+pub async fn run_scenario_yaml(
+    yaml: &str,
+    base_url: Option<&str>,
+) -> Result<TestResult> {
+    let mut scenario = TestScenario::from_yaml(yaml)?;
+    let options = RunOptions {
+        base_url: base_url.map(str::to_string),
+        ..Default::default()
+    };
+    options.apply_overrides(&mut scenario.config);
+    options.runner.run(&scenario).await
+}
+
+/// Discover the scenarios under `paths`, run them, and return the
+/// aggregated report.
+///
+/// Files that fail to load inside directories are skipped (and
+/// logged); an explicitly listed file that fails to load is an
+/// error. A scenario whose run errors is reported as a failed
+/// result without steps.
+pub async fn run_suite(
+    paths: &[PathBuf],
+    options: RunOptions,
+) -> Result<TestRunReport> {
+    let start = Instant::now();
+    let mut manager = TestConfigManager::new();
+    let scenarios = manager.load_paths(paths)?;
+    for skipped in manager.skipped_files() {
+        warn!(
+            "Skipped {} (load error): {}",
+            skipped.path.display(),
+            skipped.error
+        );
+    }
+
+    let scenarios = options.prepare(scenarios)?;
+    info!("Running {} scenario(s)...", scenarios.len());
+
+    let with_latency = options.latency_stats;
+    let mut by_index: Vec<Option<TestResult>> =
+        scenarios.iter().map(|_| None).collect();
+    execute_scenarios(
+        scenarios,
+        options,
+        std::future::pending(),
+        |idx, _, result| by_index[idx] = Some(result),
+    )
+    .await;
+    let results: Vec<TestResult> = by_index.into_iter().flatten().collect();
+
+    let latency = if with_latency {
+        latency_stats(&results)
+    } else {
+        None
+    };
+
+    Ok(TestRunReport {
+        scenarios: results,
+        total_duration_ms: start.elapsed().as_millis() as u64,
+        timestamp: Utc::now().to_rfc3339(),
+        ci: detect_ci_metadata(),
+        latency,
+    })
+}
+
+/// How an [`execute_scenarios`] call ended.
+#[derive(Debug, Default)]
+pub struct ExecutionSummary {
+    /// Scenarios aborted or never started because
+    /// [`RunOptions::fail_fast`] stopped the run.
+    pub not_run: usize,
+    /// `true` when `cancel` resolved before every scenario
+    /// finished.
+    pub interrupted: bool,
+}
+
+/// Run already prepared `scenarios` with up to
+/// [`RunOptions::concurrency`] at once, handing each result to
+/// `on_result` as it completes, along with the scenario and its
+/// index in `scenarios`.
+///
+/// A runner error or a panicked task is reported as a failed
+/// result. With [`RunOptions::fail_fast`] the first failure aborts
+/// the running scenarios and skips the rest; `cancel` resolving
+/// does the same and marks the run as interrupted.
+pub async fn execute_scenarios<F>(
+    scenarios: Vec<TestScenario>,
+    options: RunOptions,
+    cancel: impl Future<Output = ()>,
+    mut on_result: F,
+) -> ExecutionSummary
+where
+    F: FnMut(usize, &TestScenario, TestResult),
+{
+    let jobs = options.concurrency.max(1);
+    let runner = Arc::new(options.runner);
+    let scenarios: Vec<Arc<TestScenario>> =
+        scenarios.into_iter().map(Arc::new).collect();
+    let total = scenarios.len();
+    let mut pending = 0..total;
+    let mut tasks = JoinSet::new();
+    // Scenario index of each running task, so a task that panics
+    // is still reported against its scenario
+    let mut running: HashMap<tokio::task::Id, usize> = HashMap::new();
+    let mut summary = ExecutionSummary::default();
+    tokio::pin!(cancel);
+
+    loop {
+        while tasks.len() < jobs {
+            let Some(idx) = pending.next() else {
+                break;
+            };
+            let scenario = Arc::clone(&scenarios[idx]);
+            info!("Test {}/{}: {}", idx + 1, total, scenario.name);
+            let runner = Arc::clone(&runner);
+            let handle = tasks
+                .spawn(async move { run_one(&runner, &scenario).await });
+            running.insert(handle.id(), idx);
+        }
+
+        let joined = tokio::select! {
+            joined = tasks.join_next_with_id() => match joined {
+                Some(joined) => joined,
+                None => break,
+            },
+            _ = &mut cancel => {
+                warn!(
+                    "Interrupted; aborted {} running and skipped {} \
+                     remaining test(s)",
+                    tasks.len(),
+                    pending.len()
+                );
+                tasks.abort_all();
+                summary.interrupted = true;
+                break;
+            }
+        };
+        let (id, result) = match joined {
+            Ok((id, result)) => (id, Ok(result)),
+            Err(e) => (e.id(), Err(e)),
+        };
+        let idx = running.remove(&id).expect("task was not registered");
+        let result = result.unwrap_or_else(|e| {
+            warn!("Scenario task panicked: {}", e);
+            failed_result(
+                scenarios[idx].name.clone(),
+                format!("Scenario task panicked: {e}"),
+            )
+        });
+        let success = result.success;
+        on_result(idx, &scenarios[idx], result);
+
+        if options.fail_fast && !success {
+            summary.not_run = tasks.len() + pending.len();
+            if summary.not_run > 0 {
+                warn!(
+                    "Fail-fast: aborted {} running and skipped {} \
+                     remaining test(s)",
+                    tasks.len(),
+                    pending.len()
+                );
+            }
+            tasks.abort_all();
+            break;
+        }
+    }
+    summary
+}
+
+/// Run one scenario, turning a runner error into a failed result.
+pub(crate) async fn run_one(
+    runner: &DefaultTestRunner,
+    scenario: &TestScenario,
+) -> TestResult {
+    match runner.run(scenario).await {
+        Ok(result) => result,
+        Err(e) => failed_result(
+            scenario.name.clone(),
+            format!("Test execution error: {e}"),
+        ),
+    }
+}
+
+/// A failed result without steps for a scenario that did not run
+/// to completion.
+fn failed_result(name: String, error: String) -> TestResult {
+    TestResult {
+        name,
+        success: false,
+        error: Some(error),
+        steps: vec![],
+        duration_ms: 0,
+        outputs: HashMap::new(),
+    }
+}
+
+/// Compute latency statistics over every executed step in
+/// `results`. Returns `None` when no step ran.
+pub fn latency_stats(results: &[TestResult]) -> Option<LatencyStats> {
+    latency_from_durations(
+        results
+            .iter()
+            .flat_map(|r| &r.steps)
+            .filter(|s| !s.skipped)
+            .map(|s| s.duration_ms)
+            .collect(),
+    )
+}
+
+/// Compute latency statistics over step durations in
+/// milliseconds. Returns `None` for an empty list.
+pub(crate) fn latency_from_durations(
+    mut durations: Vec<u64>,
+) -> Option<LatencyStats> {
+    if durations.is_empty() {
+        return None;
+    }
+    durations.sort_unstable();
+    let count = durations.len();
+    Some(LatencyStats {
+        count,
+        min_ms: durations[0],
+        mean_ms: durations.iter().sum::<u64>() / count as u64,
+        p50_ms: percentile(&durations, 50.0),
+        p95_ms: percentile(&durations, 95.0),
+        p99_ms: percentile(&durations, 99.0),
+        max_ms: durations[count - 1],
+    })
+}
+
+/// Detect CI metadata from environment variables.
+pub fn detect_ci_metadata() -> Option<CiMetadata> {
+    // GitHub Actions
+    if std::env::var("GITHUB_ACTIONS").is_ok() {
+        let repository =
+            std::env::var("GITHUB_REPOSITORY").unwrap_or_default();
+        let branch = std::env::var("GITHUB_REF_NAME")
+            .or_else(|_| std::env::var("GITHUB_HEAD_REF"))
+            .unwrap_or_default();
+        let commit_sha = std::env::var("GITHUB_SHA").unwrap_or_default();
+        let pr_number = std::env::var("PR_NUMBER")
+            .or_else(|_| {
+                // Try to extract from GITHUB_REF (refs/pull/123/merge)
+                std::env::var("GITHUB_REF")
+                    .map(|r| r.split('/').nth(2).unwrap_or("").to_string())
+            })
+            .ok()
+            .and_then(|n| n.parse::<u64>().ok());
+        let run_id = std::env::var("GITHUB_RUN_ID").ok();
+        let run_url = run_id.as_ref().map(|id| {
+            format!("https://github.com/{repository}/actions/runs/{id}")
+        });
+
+        return Some(CiMetadata {
+            provider: "github".to_string(),
+            repository,
+            branch,
+            commit_sha,
+            pr_number,
+            run_id,
+            run_url,
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{RequestInfo, StepResult};
+
+    fn step(duration_ms: u64, skipped: bool) -> StepResult {
+        StepResult {
+            name: "s".to_string(),
+            success: true,
+            error: None,
+            request: RequestInfo {
+                method: "GET".to_string(),
+                url: "/".to_string(),
+                headers: HashMap::new(),
+                query: HashMap::new(),
+                body: None,
+            },
+            response: None,
+            duration_ms,
+            vars_snapshot: None,
+            skipped,
+            skip_reason: None,
+            assertions: Vec::new(),
+            diff: Vec::new(),
+            attempts: 1,
+        }
+    }
+
+    fn result(steps: Vec<StepResult>) -> TestResult {
+        TestResult {
+            name: "scenario".to_string(),
+            success: true,
+            error: None,
+            steps,
+            duration_ms: 0,
+            outputs: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_apply_vars_layers_defaults_and_overrides() {
+        let mut scenario = TestScenario::from_yaml(
+            "name: s\nvars: {a: scenario, b: scenario}\nsteps: []\n",
+        )
+        .unwrap();
+        let options = RunOptions {
+            default_vars: HashMap::from([
+                ("a".to_string(), "default".into()),
+                ("c".to_string(), "default".into()),
+            ]),
+            override_vars: HashMap::from([(
+                "b".to_string(),
+                "override".into(),
+            )]),
+            ..Default::default()
+        };
+        options.apply_vars(&mut scenario);
+        assert_eq!(scenario.vars["a"], "scenario");
+        assert_eq!(scenario.vars["b"], "override");
+        assert_eq!(scenario.vars["c"], "default");
+    }
+
+    #[test]
+    fn test_latency_stats_across_scenarios() {
+        let results = vec![
+            result((1..=50).map(|ms| step(ms, false)).collect()),
+            result((51..=100).map(|ms| step(ms, false)).collect()),
+        ];
+        let stats = latency_stats(&results).unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.min_ms, 1);
+        assert_eq!(stats.mean_ms, 50);
+        assert_eq!(stats.p50_ms, 50);
+        assert_eq!(stats.p95_ms, 95);
+        assert_eq!(stats.p99_ms, 99);
+        assert_eq!(stats.max_ms, 100);
+    }
+
+    #[test]
+    fn test_latency_stats_ignores_skipped_steps() {
+        let results = vec![result(vec![step(0, true), step(7, false)])];
+        let stats = latency_stats(&results).unwrap();
+        assert_eq!(stats.count, 1);
+        assert_eq!(stats.min_ms, 7);
+
+        assert!(latency_stats(&[result(vec![step(0, true)])]).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! Peer certificate inspection for `expect.cert`.
+
+use crate::model::{CertExpectation, CertificateInfo};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+/// Parse the subject, issuer, and expiry of a DER certificate.
+pub fn parse_certificate(der: &[u8]) -> Result<CertificateInfo> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der)
+        .map_err(|e| anyhow!("failed to parse peer certificate: {e}"))?;
+    let not_after =
+        DateTime::from_timestamp(cert.validity().not_after.timestamp(), 0)
+            .ok_or_else(|| anyhow!("certificate expiry is out of range"))?;
+    Ok(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        not_after: not_after.to_rfc3339(),
+    })
+}
+
+/// Check `cert` against `expect` as of `now`, returning one error
+/// per violated expectation.
+pub fn check_certificate(
+    expect: &CertExpectation,
+    cert: &CertificateInfo,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Some(min_days) = expect.expires_after_days {
+        match DateTime::parse_from_rfc3339(&cert.not_after) {
+            Ok(not_after) => {
+                let days_left =
+                    (not_after.with_timezone(&Utc) - now).num_days();
+                if days_left < min_days as i64 {
+                    errors.push(format!(
+                        "cert: 証明書の有効期限まで残り {days_left} 日です \
+                         ({})。期待: {min_days} 日以上",
+                        cert.not_after
+                    ));
+                }
+            }
+            Err(e) => errors.push(format!(
+                "cert: 不正な有効期限 '{}': {e}",
+                cert.not_after
+            )),
+        }
+    }
+    if let Some(needle) = &expect.issuer_contains {
+        if !cert.issuer.contains(needle.as_str()) {
+            errors.push(format!(
+                "cert: 発行者 '{}' に '{needle}' が含まれていません",
+                cert.issuer
+            ));
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn self_signed_der(days_valid: i64) -> Vec<u8> {
+        let mut params =
+            rcgen::CertificateParams::new(vec!["api.example.com".into()])
+                .unwrap();
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "Example Test CA");
+        let not_after = Utc::now() + Duration::days(days_valid);
+        params.not_after = rcgen::date_time_ymd(
+            not_after.format("%Y").to_string().parse().unwrap(),
+            not_after.format("%m").to_string().parse().unwrap(),
+            not_after.format("%d").to_string().parse().unwrap(),
+        );
+        let key = rcgen::KeyPair::generate().unwrap();
+        params.self_signed(&key).unwrap().der().to_vec()
+    }
+
+    #[test]
+    fn test_parse_certificate_details() {
+        let info = parse_certificate(&self_signed_der(30)).unwrap();
+        assert!(info.subject.contains("Example Test CA"));
+        assert!(info.issuer.contains("Example Test CA"));
+        assert!(DateTime::parse_from_rfc3339(&info.not_after).is_ok());
+    }
+
+    #[test]
+    fn test_check_certificate_expiry_threshold() {
+        let info = parse_certificate(&self_signed_der(10)).unwrap();
+        let now = Utc::now();
+
+        let ok = CertExpectation {
+            expires_after_days: Some(5),
+            issuer_contains: Some("Example".to_string()),
+        };
+        assert!(check_certificate(&ok, &info, now).is_empty());
+
+        let too_soon = CertExpectation {
+            expires_after_days: Some(30),
+            issuer_contains: Some("Other CA".to_string()),
+        };
+        let errors = check_certificate(&too_soon, &info, now);
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors[0].contains("期待: 30 日以上"));
+        assert!(errors[1].contains("に 'Other CA' が含まれていません"));
+    }
+
+    #[test]
+    fn test_parse_certificate_rejects_garbage() {
+        assert!(parse_certificate(b"not a certificate").is_err());
+    }
+}
@@ -1,9 +1,87 @@
 //! TODO: add English documentation
 
+use crate::model::{CsvExpectation, DataMismatch, MismatchKind};
 use anyhow::{anyhow, Result};
-use serde_json::Value;
+use serde_json::{Number, Value};
 use std::collections::HashMap;
 
+/// Options controlling how JSON values are compared.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompareOptions {
+    /// Compare numbers by value so that `1` equals `1.0`.
+    pub numeric_loose: bool,
+    /// Treat a string holding a JSON number literal as that
+    /// number, so `"123"` equals `123` (either direction).
+    pub coerce_numeric_strings: bool,
+}
+
+/// Compare two JSON values for equality according to `opts`.
+///
+/// With default options this is plain `==`. With
+/// `numeric_loose`, numbers are compared by value at any depth.
+/// With `coerce_numeric_strings`, a string and a number are equal
+/// when the string parses as a JSON number equal to the other side;
+/// strings with whitespace, signs like `+`, or leading zeros never
+/// match.
+pub fn values_equal(
+    actual: &Value,
+    expected: &Value,
+    opts: CompareOptions,
+) -> bool {
+    if !opts.numeric_loose && !opts.coerce_numeric_strings {
+        return actual == expected;
+    }
+
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(e)) => compare_numbers(a, e, opts),
+        (Value::String(s), Value::Number(n))
+        | (Value::Number(n), Value::String(s))
+            if opts.coerce_numeric_strings =>
+        {
+            s.parse::<Number>()
+                .is_ok_and(|parsed| compare_numbers(&parsed, n, opts))
+        }
+        (Value::Array(a_arr), Value::Array(e_arr)) => {
+            a_arr.len() == e_arr.len()
+                && a_arr
+                    .iter()
+                    .zip(e_arr)
+                    .all(|(a, e)| values_equal(a, e, opts))
+        }
+        (Value::Object(a_map), Value::Object(e_map)) => {
+            a_map.len() == e_map.len()
+                && a_map.iter().all(|(k, a)| {
+                    e_map.get(k).is_some_and(|e| values_equal(a, e, opts))
+                })
+        }
+        _ => actual == expected,
+    }
+}
+
+/// Compare two JSON numbers, by value when `numeric_loose` is set.
+fn compare_numbers(a: &Number, b: &Number, opts: CompareOptions) -> bool {
+    if opts.numeric_loose {
+        numbers_equal(a, b)
+    } else {
+        a == b
+    }
+}
+
+/// Compare two JSON numbers by value, keeping integer precision
+/// when both sides are integers.
+fn numbers_equal(a: &Number, b: &Number) -> bool {
+    if let (Some(x), Some(y)) = (a.as_i64(), b.as_i64()) {
+        return x == y;
+    }
+    if let (Some(x), Some(y)) = (a.as_u64(), b.as_u64()) {
+        return x == y;
+    }
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => false,
+    }
+}
+
 /// TODO: add English documentation
 pub fn get_by_json_path<'a>(
     json: &'a Value,
@@ -83,45 +161,180 @@ pub fn validate_contains(body: &str, texts: &[String]) -> Vec<String> {
     errors
 }
 
+/// One segment of a parsed ignore pattern.
+#[derive(Debug, PartialEq)]
+enum PatternSegment<'a> {
+    /// Matches the segment literally.
+    Literal(&'a str),
+    /// `*` — matches any single segment.
+    Any,
+    /// `**` — matches zero or more segments.
+    AnyDeep,
+    /// `[start:end]` / `[index]` — matches a numeric array index
+    /// in the half-open range `start..end` (either bound may be
+    /// omitted).
+    Range(Option<usize>, Option<usize>),
+}
+
+/// Parse an ignore pattern such as `items[0:2].meta.**` into
+/// segments. `name[range]` expands to the literal `name` followed
+/// by the range. Returns `None` for malformed ranges so the
+/// pattern never matches.
+fn parse_ignore_pattern(pattern: &str) -> Option<Vec<PatternSegment<'_>>> {
+    let mut segments = Vec::new();
+    for part in pattern.split('.') {
+        let (name, range) = match part.find('[') {
+            Some(open) if part.ends_with(']') => {
+                (&part[..open], Some(&part[open + 1..part.len() - 1]))
+            }
+            _ => (part, None),
+        };
+        match name {
+            "" if range.is_some() => {}
+            "*" => segments.push(PatternSegment::Any),
+            "**" => segments.push(PatternSegment::AnyDeep),
+            _ => segments.push(PatternSegment::Literal(name)),
+        }
+        if let Some(range) = range {
+            segments.push(parse_index_range(range)?);
+        }
+    }
+    Some(segments)
+}
+
+/// Parse the inside of `[...]`: `start:end`, `start:`, `:end`, or
+/// a single `index`.
+fn parse_index_range(range: &str) -> Option<PatternSegment<'static>> {
+    let bound = |s: &str| -> Option<Option<usize>> {
+        if s.is_empty() {
+            Some(None)
+        } else {
+            s.trim().parse().ok().map(Some)
+        }
+    };
+    match range.split_once(':') {
+        Some((start, end)) => {
+            Some(PatternSegment::Range(bound(start)?, bound(end)?))
+        }
+        None => {
+            let index: usize = range.trim().parse().ok()?;
+            Some(PatternSegment::Range(Some(index), Some(index + 1)))
+        }
+    }
+}
+
+/// Match path segments against pattern segments.
+fn segments_match(pattern: &[PatternSegment<'_>], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((PatternSegment::AnyDeep, rest)) => {
+            (0..=path.len()).any(|skip| segments_match(rest, &path[skip..]))
+        }
+        Some((segment, rest)) => {
+            let Some((head, tail)) = path.split_first() else {
+                return false;
+            };
+            let matched = match segment {
+                PatternSegment::Literal(lit) => lit == head,
+                PatternSegment::Any => true,
+                PatternSegment::Range(start, end) => {
+                    head.parse::<usize>().is_ok_and(|i| {
+                        start.is_none_or(|s| i >= s)
+                            && end.is_none_or(|e| i < e)
+                    })
+                }
+                PatternSegment::AnyDeep => unreachable!(),
+            };
+            matched && segments_match(rest, tail)
+        }
+    }
+}
+
 /// Check whether the given dot-separated path should be ignored.
 ///
-/// Each segment of `path` is compared against each segment of each
-/// ignore pattern. The wildcard `*` in a pattern matches any single
-/// segment (including numeric array indices).
+/// Paths address array elements by numeric segments
+/// (`items.0.id`). Pattern segments match as follows:
+///
+/// - a literal segment matches itself;
+/// - `*` matches any single segment (including array indices);
+/// - `**` matches zero or more segments, so `meta.**` ignores
+///   `meta` and everything below it;
+/// - `[start:end]` matches an array index in `start..end`
+///   (end-exclusive, either bound optional) and `[n]` matches
+///   index `n`; `items[0:2].id` is shorthand for
+///   `items.[0:2].id`.
+///
+/// Malformed ranges never match.
 fn is_field_ignored(path: &str, ignore_fields: &[String]) -> bool {
     let path_parts: Vec<&str> = path.split('.').collect();
-    for pattern in ignore_fields {
-        let pat_parts: Vec<&str> = pattern.split('.').collect();
-        if pat_parts.len() != path_parts.len() {
-            continue;
-        }
-        let matched = pat_parts
-            .iter()
-            .zip(path_parts.iter())
-            .all(|(p, a)| *p == "*" || *p == *a);
-        if matched {
-            return true;
-        }
-    }
-    false
+    ignore_fields.iter().any(|pattern| {
+        parse_ignore_pattern(pattern)
+            .is_some_and(|segments| segments_match(&segments, &path_parts))
+    })
 }
 
 /// Recursively compare two JSON values for full equality,
 /// skipping fields listed in `ignore_fields`.
 ///
 /// Returns a list of human-readable error strings describing
-/// every mismatch found (empty = values are equal).
+/// every mismatch found (empty = values are equal); see
+/// [`diff_data_eq`] for the structured form. Scalars are compared
+/// with [`values_equal`] using `opts`.
 pub fn validate_data_eq(
     actual: &Value,
     expected: &Value,
     ignore_fields: &[String],
     path_prefix: &str,
+    opts: CompareOptions,
 ) -> Vec<String> {
+    diff_data_eq(actual, expected, ignore_fields, path_prefix, opts)
+        .iter()
+        .map(DataMismatch::message)
+        .collect()
+}
+
+/// Like [`validate_data_eq`], but returns every mismatch as a
+/// [`DataMismatch`] carrying its path and both values.
+pub fn diff_data_eq(
+    actual: &Value,
+    expected: &Value,
+    ignore_fields: &[String],
+    path_prefix: &str,
+    opts: CompareOptions,
+) -> Vec<DataMismatch> {
+    let mut diff = Vec::new();
+    push_data_diff(
+        &mut diff,
+        actual,
+        expected,
+        ignore_fields,
+        path_prefix,
+        opts,
+    );
+    diff
+}
+
+fn push_data_diff(
+    diff: &mut Vec<DataMismatch>,
+    actual: &Value,
+    expected: &Value,
+    ignore_fields: &[String],
+    path_prefix: &str,
+    opts: CompareOptions,
+) {
     if is_field_ignored(path_prefix, ignore_fields) {
-        return vec![];
+        return;
     }
 
-    let mut errors = Vec::new();
+    let mismatch =
+        |kind, expected: Option<&Value>, actual: Option<&Value>| {
+            DataMismatch {
+                path: path_prefix.to_string(),
+                kind,
+                expected: expected.cloned(),
+                actual: actual.cloned(),
+            }
+        };
 
     match (actual, expected) {
         (Value::Object(a_map), Value::Object(e_map)) => {
@@ -144,26 +357,32 @@ pub fn validate_data_eq(
 
                 match (a_map.get(key), e_map.get(key)) {
                     (Some(a_val), Some(e_val)) => {
-                        errors.extend(validate_data_eq(
+                        push_data_diff(
+                            diff,
                             a_val,
                             e_val,
                             ignore_fields,
                             &child_path,
-                        ));
+                            opts,
+                        );
                     }
                     (Some(a_val), None) => {
                         if !a_val.is_null() {
-                            errors.push(format!(
-                                "data_eq '{child_path}': unexpected field \
-                                 (value: {a_val:?})"
-                            ));
+                            diff.push(DataMismatch {
+                                path: child_path,
+                                kind: MismatchKind::Unexpected,
+                                expected: None,
+                                actual: Some(a_val.clone()),
+                            });
                         }
                     }
                     (None, Some(e_val)) => {
-                        errors.push(format!(
-                            "data_eq '{child_path}': missing field \
-                             (expected: {e_val:?})"
-                        ));
+                        diff.push(DataMismatch {
+                            path: child_path,
+                            kind: MismatchKind::Missing,
+                            expected: Some(e_val.clone()),
+                            actual: None,
+                        });
                     }
                     (None, None) => unreachable!(),
                 }
@@ -171,12 +390,10 @@ pub fn validate_data_eq(
         }
         (Value::Array(a_arr), Value::Array(e_arr)) => {
             if a_arr.len() != e_arr.len() {
-                errors.push(format!(
-                    "data_eq '{}': array length mismatch — \
-                     expected {}, got {}",
-                    path_prefix,
-                    e_arr.len(),
-                    a_arr.len()
+                diff.push(mismatch(
+                    MismatchKind::Length,
+                    Some(&e_arr.len().into()),
+                    Some(&a_arr.len().into()),
                 ));
             }
             let len = std::cmp::min(a_arr.len(), e_arr.len());
@@ -186,25 +403,26 @@ pub fn validate_data_eq(
                 } else {
                     format!("{path_prefix}.{i}")
                 };
-                errors.extend(validate_data_eq(
+                push_data_diff(
+                    diff,
                     &a_arr[i],
                     &e_arr[i],
                     ignore_fields,
                     &child_path,
-                ));
+                    opts,
+                );
             }
         }
         _ => {
-            if actual != expected {
-                errors.push(format!(
-                    "data_eq '{path_prefix}': value mismatch — \
-                     expected {expected:?}, got {actual:?}"
+            if !values_equal(actual, expected, opts) {
+                diff.push(mismatch(
+                    MismatchKind::Value,
+                    Some(expected),
+                    Some(actual),
                 ));
             }
         }
     }
-
-    errors
 }
 
 /// TODO: add English documentation
@@ -233,3 +451,411 @@ pub fn validate_headers(
 
     errors
 }
+
+/// Validate `instance` against a JSON Schema (draft 2020-12).
+///
+/// Returns one message per violation, prefixed with the JSON
+/// Pointer of the offending value, or a single message when the
+/// schema itself is invalid.
+pub fn validate_schema(instance: &Value, schema: &Value) -> Vec<String> {
+    let validator = match jsonschema::draft202012::new(schema) {
+        Ok(validator) => validator,
+        Err(e) => return vec![format!("schema: 不正なJSON Schema: {e}")],
+    };
+    validator
+        .iter_errors(instance)
+        .map(|e| {
+            let path = e.instance_path().as_str();
+            let path = if path.is_empty() { "/" } else { path };
+            format!("schema: {path}: {e}")
+        })
+        .collect()
+}
+
+/// Validate a CSV `body` against `expected`.
+///
+/// Returns one message per failed check, or a single message when
+/// the body is not valid CSV.
+pub fn validate_csv(body: &str, expected: &CsvExpectation) -> Vec<String> {
+    let records = match parse_csv(body) {
+        Ok(records) => records,
+        Err(e) => {
+            return vec![format!(
+                "csv: レスポンスが有効なCSVではありません ({e})"
+            )]
+        }
+    };
+    let (header, rows) = match records.split_first() {
+        Some((header, rows)) => (header.as_slice(), rows),
+        None => (&[][..], &[][..]),
+    };
+    let mut errors = Vec::new();
+
+    if let Some(count) = expected.row_count {
+        if rows.len() != count {
+            errors.push(format!(
+                "csv: 行数が期待値と一致しません。\
+                 期待: {count}, 実際: {}",
+                rows.len()
+            ));
+        }
+    }
+    if let Some(names) = &expected.headers {
+        if header != names.as_slice() {
+            errors.push(format!(
+                "csv: ヘッダー行が期待値と一致しません。\
+                 期待: {}, 実際: {}",
+                names.join(","),
+                header.join(",")
+            ));
+        }
+    }
+
+    let mut cells: Vec<_> = expected.cell.iter().collect();
+    cells.sort();
+    for (address, value) in cells {
+        let Some((row, column)) = address.split_once('.') else {
+            errors.push(format!(
+                "csv: 不正なセルアドレス '{address}' \
+                 (行.列 の形式で指定してください)"
+            ));
+            continue;
+        };
+        let column = header
+            .iter()
+            .position(|name| name == column)
+            .or_else(|| column.parse().ok());
+        let actual = row
+            .parse::<usize>()
+            .ok()
+            .and_then(|row| rows.get(row))
+            .zip(column)
+            .and_then(|(row, column)| row.get(column));
+        match actual {
+            Some(actual) if actual != value => errors.push(format!(
+                "csv: セル '{address}' の値が期待値と一致しません。\
+                 期待: {value}, 実際: {actual}"
+            )),
+            Some(_) => {}
+            None => errors.push(format!(
+                "csv: セル '{address}' がレスポンスに存在しません"
+            )),
+        }
+    }
+
+    errors
+}
+
+/// Split CSV text into records (RFC 4180): comma separated,
+/// double-quoted fields may contain commas, line breaks and `""`
+/// escapes. A trailing line break does not start a new record.
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut at_field_start = true;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if at_field_start => {
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => field.push(c),
+                        None => {
+                            return Err(anyhow!(
+                                "引用符で囲まれたフィールドが閉じていません"
+                            ))
+                        }
+                    }
+                }
+                if !matches!(chars.peek(), None | Some(',' | '\r' | '\n')) {
+                    return Err(anyhow!(
+                        "引用符で囲まれたフィールド '{field}' の後に\
+                         不正なテキストがあります"
+                    ));
+                }
+                at_field_start = false;
+            }
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                at_field_start = true;
+            }
+            '\r' | '\n' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                at_field_start = true;
+            }
+            c => {
+                field.push(c);
+                at_field_start = false;
+            }
+        }
+    }
+    if !at_field_start || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LOOSE: CompareOptions = CompareOptions {
+        numeric_loose: true,
+        coerce_numeric_strings: false,
+    };
+
+    const COERCE: CompareOptions = CompareOptions {
+        numeric_loose: false,
+        coerce_numeric_strings: true,
+    };
+
+    #[test]
+    fn test_validate_schema_reports_each_violation() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {"id": {"type": "integer"}}
+        });
+        assert!(validate_schema(&json!({"id": 1, "name": "a"}), &schema)
+            .is_empty());
+
+        let errors = validate_schema(&json!({"id": "x"}), &schema);
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors.iter().any(|e| e.starts_with("schema: /id:")));
+        assert!(errors.iter().any(|e| e.starts_with("schema: /:")));
+    }
+
+    #[test]
+    fn test_validate_schema_rejects_invalid_schema() {
+        let errors = validate_schema(&json!(1), &json!({"type": 5}));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("不正なJSON Schema"), "{errors:?}");
+    }
+
+    #[test]
+    fn test_parse_csv_handles_quotes_and_line_endings() {
+        let records =
+            parse_csv("id,name\r\n1,\"Ada, \"\"the\"\"\"\n2,\"a\nb\"\n")
+                .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["id", "name"],
+                vec!["1", "Ada, \"the\""],
+                vec!["2", "a\nb"],
+            ]
+        );
+        assert_eq!(parse_csv("").unwrap(), Vec::<Vec<String>>::new());
+        assert_eq!(parse_csv("a,").unwrap(), vec![vec!["a", ""]]);
+        assert!(parse_csv("\"open").is_err());
+        assert!(parse_csv("\"a\"b").is_err());
+    }
+
+    #[test]
+    fn test_validate_csv_checks_rows_headers_and_cells() {
+        let body = "id,name\n1,Ada\n2,Bob\n";
+        let expected = CsvExpectation {
+            row_count: Some(2),
+            headers: Some(vec!["id".into(), "name".into()]),
+            cell: HashMap::from([
+                ("0.name".into(), "Ada".into()),
+                ("1.0".into(), "2".into()),
+            ]),
+        };
+        assert!(validate_csv(body, &expected).is_empty());
+
+        let expected = CsvExpectation {
+            row_count: Some(3),
+            headers: Some(vec!["id".into()]),
+            cell: HashMap::from([
+                ("1.name".into(), "Ada".into()),
+                ("5.name".into(), "x".into()),
+                ("name".into(), "x".into()),
+            ]),
+        };
+        assert_eq!(
+            validate_csv(body, &expected),
+            vec![
+                "csv: 行数が期待値と一致しません。期待: 3, 実際: 2",
+                "csv: ヘッダー行が期待値と一致しません。\
+                 期待: id, 実際: id,name",
+                "csv: セル '1.name' の値が期待値と一致しません。\
+                 期待: Ada, 実際: Bob",
+                "csv: セル '5.name' がレスポンスに存在しません",
+                "csv: 不正なセルアドレス 'name' (行.列 の形式で指定してください)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_equal_strict_by_default() {
+        let opts = CompareOptions::default();
+        assert!(values_equal(&json!(1), &json!(1), opts));
+        assert!(!values_equal(&json!(1.0), &json!(1), opts));
+    }
+
+    #[test]
+    fn test_values_equal_numeric_loose() {
+        assert!(values_equal(&json!(1.0), &json!(1), LOOSE));
+        assert!(values_equal(&json!(-3), &json!(-3.0), LOOSE));
+        assert!(!values_equal(&json!(1.5), &json!(1), LOOSE));
+        assert!(!values_equal(&json!("1"), &json!(1), LOOSE));
+    }
+
+    #[test]
+    fn test_values_equal_numeric_loose_nested() {
+        assert!(values_equal(
+            &json!({"a": [1.0, 2.0], "b": {"c": 3.0}}),
+            &json!({"a": [1, 2], "b": {"c": 3}}),
+            LOOSE,
+        ));
+        assert!(!values_equal(
+            &json!({"a": [1.0]}),
+            &json!({"a": [1], "extra": 1}),
+            LOOSE,
+        ));
+    }
+
+    #[test]
+    fn test_validate_data_eq_numeric_loose() {
+        let actual = json!({"count": 2.0, "items": [{"n": 1.0}]});
+        let expected = json!({"count": 2, "items": [{"n": 1}]});
+
+        let strict = validate_data_eq(
+            &actual,
+            &expected,
+            &[],
+            "",
+            CompareOptions::default(),
+        );
+        assert_eq!(strict.len(), 2, "Errors: {strict:?}");
+
+        let loose = validate_data_eq(&actual, &expected, &[], "", LOOSE);
+        assert!(loose.is_empty(), "Errors: {loose:?}");
+    }
+
+    #[test]
+    fn test_values_equal_coerce_numeric_strings() {
+        assert!(values_equal(&json!("123"), &json!(123), COERCE));
+        assert!(values_equal(&json!(123), &json!("123"), COERCE));
+        assert!(values_equal(&json!("-1.5"), &json!(-1.5), COERCE));
+        assert!(values_equal(
+            &json!({"ids": ["1", 2]}),
+            &json!({"ids": [1, "2"]}),
+            COERCE,
+        ));
+        assert!(!values_equal(&json!("124"), &json!(123), COERCE));
+        assert!(!values_equal(&json!(" 123"), &json!(123), COERCE));
+        assert!(!values_equal(&json!("0123"), &json!(123), COERCE));
+        assert!(!values_equal(&json!("abc"), &json!(123), COERCE));
+        // Strings and numbers are never equal without the option.
+        assert!(!values_equal(
+            &json!("123"),
+            &json!(123),
+            CompareOptions::default()
+        ));
+    }
+
+    #[test]
+    fn test_values_equal_coerce_respects_numeric_loose() {
+        assert!(!values_equal(&json!("1.0"), &json!(1), COERCE));
+        let both = CompareOptions {
+            numeric_loose: true,
+            coerce_numeric_strings: true,
+        };
+        assert!(values_equal(&json!("1.0"), &json!(1), both));
+    }
+
+    // ── is_field_ignored patterns ───────────────────────
+
+    fn ignored(path: &str, pattern: &str) -> bool {
+        is_field_ignored(path, &[pattern.to_string()])
+    }
+
+    #[test]
+    fn test_ignore_single_wildcard() {
+        assert!(ignored("items.0.id", "items.*.id"));
+        assert!(!ignored("items.0.name", "items.*.id"));
+        assert!(!ignored("items.0.id.x", "items.*.id"));
+    }
+
+    #[test]
+    fn test_ignore_trailing_deep_wildcard() {
+        assert!(ignored("meta", "meta.**"));
+        assert!(ignored("meta.created_at", "meta.**"));
+        assert!(ignored("meta.trace.spans.0.id", "meta.**"));
+        assert!(!ignored("metadata.x", "meta.**"));
+        assert!(!ignored("data.meta.x", "meta.**"));
+    }
+
+    #[test]
+    fn test_ignore_inner_deep_wildcard() {
+        assert!(ignored("a.b.c.updated_at", "a.**.updated_at"));
+        assert!(ignored("a.updated_at", "a.**.updated_at"));
+        assert!(!ignored("a.b.created_at", "a.**.updated_at"));
+    }
+
+    #[test]
+    fn test_ignore_index_range() {
+        assert!(ignored("items.0.id", "items[0:2].id"));
+        assert!(ignored("items.1.id", "items[0:2].id"));
+        assert!(!ignored("items.2.id", "items[0:2].id"));
+        assert!(!ignored("items.x.id", "items[0:2].id"));
+        assert!(ignored("items.5", "items[3:]"));
+        assert!(!ignored("items.2", "items[3:]"));
+        assert!(ignored("items.0", "items[:1]"));
+        assert!(ignored("items.4.id", "items[4].id"));
+        assert!(!ignored("items.3.id", "items[4].id"));
+        assert!(ignored("0.id", "[0:1].id"));
+    }
+
+    #[test]
+    fn test_ignore_malformed_range_never_matches() {
+        assert!(!ignored("items.0", "items[a:b]"));
+        assert!(!ignored("items.0", "items[0-2]"));
+    }
+
+    #[test]
+    fn test_validate_data_eq_with_deep_and_range_patterns() {
+        let actual = json!({
+            "items": [
+                {"id": "x", "v": 1},
+                {"id": "y", "v": 2},
+                {"id": "z", "v": 3}
+            ],
+            "meta": {"trace": {"id": "abc", "spans": [1, 2]}}
+        });
+        let expected = json!({
+            "items": [
+                {"id": "a", "v": 1},
+                {"id": "b", "v": 2},
+                {"id": "z", "v": 3}
+            ],
+            "meta": {}
+        });
+        let ignore =
+            vec!["items[0:2].id".to_string(), "meta.**".to_string()];
+
+        let errors = validate_data_eq(
+            &actual,
+            &expected,
+            &ignore,
+            "",
+            CompareOptions::default(),
+        );
+        assert!(errors.is_empty(), "Errors: {errors:?}");
+    }
+}
@@ -0,0 +1,402 @@
+//! Minimal XML parsing and path lookup for `expect.xpath`.
+//!
+//! Only what assertions need is supported: elements, attributes,
+//! text and CDATA, the predefined and numeric entities, and
+//! skipping of the prolog, comments, processing instructions and
+//! `DOCTYPE`. Namespaces are not resolved; a path segment without
+//! a prefix matches an element by its local name.
+
+use anyhow::{anyhow, bail, Result};
+
+/// An XML element with its attributes and child nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    /// Qualified name, including any namespace prefix.
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+/// A child of an [`Element`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    /// Concatenated text of the element and its descendants.
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        for child in &self.children {
+            match child {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(element) => out.push_str(&element.text()),
+            }
+        }
+        out
+    }
+
+    /// Value of the attribute `name`, matched like element names.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| name_matches(name, key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child_elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn descendants<'a>(&'a self, out: &mut Vec<&'a Element>) {
+        for child in self.child_elements() {
+            out.push(child);
+            child.descendants(out);
+        }
+    }
+}
+
+/// Parse an XML document and return its root element.
+pub fn parse(text: &str) -> Result<Element> {
+    let mut parser = Parser { text, pos: 0 };
+    parser.skip_misc()?;
+    if !parser.rest().starts_with('<') {
+        bail!("expected a root element at byte {}", parser.pos);
+    }
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if !parser.rest().is_empty() {
+        bail!("unexpected content after the root element");
+    }
+    Ok(root)
+}
+
+/// Resolve an XPath-like `path` against `root` and return the
+/// text of the first match, or `None` when nothing matches.
+///
+/// Paths are `/`-separated element names starting at the root
+/// (`/Envelope/Body/Result`); a leading `//` searches all
+/// descendants for the first segment. A segment may be `*` and
+/// may carry a 1-based position (`item[2]`). The last segment may
+/// be `@name` for an attribute or `text()`; otherwise the
+/// element's text is returned. Text is trimmed.
+pub fn select(root: &Element, path: &str) -> Result<Option<String>> {
+    let (descendant, rest) = match path.strip_prefix("//") {
+        Some(rest) => (true, rest),
+        None => (false, path.strip_prefix('/').unwrap_or(path)),
+    };
+    let mut segments: Vec<&str> = rest.split('/').collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        bail!("不正なXPath '{path}'");
+    }
+
+    let mut attribute = None;
+    if let Some(last) = segments.last() {
+        if let Some(name) = last.strip_prefix('@') {
+            attribute = Some(name);
+            segments.pop();
+        } else if *last == "text()" {
+            segments.pop();
+        }
+    }
+    if segments.is_empty() {
+        bail!("不正なXPath '{path}': 要素のステップがありません");
+    }
+
+    let steps = segments
+        .iter()
+        .map(|s| {
+            Step::parse(s).ok_or_else(|| anyhow!("不正なXPath '{path}'"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let first = &steps[0];
+    let mut current: Vec<&Element> = if descendant {
+        let mut all = vec![root];
+        root.descendants(&mut all);
+        first.filter(all)
+    } else {
+        first.filter(vec![root])
+    };
+    for step in &steps[1..] {
+        let children =
+            current.iter().flat_map(|e| e.child_elements()).collect();
+        current = step.filter(children);
+    }
+
+    let Some(element) = current.first() else {
+        return Ok(None);
+    };
+    Ok(match attribute {
+        Some(name) => element.attribute(name).map(|v| v.trim().to_string()),
+        None => Some(element.text().trim().to_string()),
+    })
+}
+
+/// One element step of a path: a name test and optional position.
+struct Step<'a> {
+    name: &'a str,
+    position: Option<usize>,
+}
+
+impl<'a> Step<'a> {
+    fn parse(segment: &'a str) -> Option<Self> {
+        match segment.split_once('[') {
+            Some((name, index)) => {
+                let position =
+                    index.strip_suffix(']')?.trim().parse().ok()?;
+                (position > 0).then_some(Self {
+                    name,
+                    position: Some(position),
+                })
+            }
+            None => Some(Self {
+                name: segment,
+                position: None,
+            }),
+        }
+    }
+
+    fn filter<'e>(&self, candidates: Vec<&'e Element>) -> Vec<&'e Element> {
+        let matched = candidates.into_iter().filter(|e| {
+            self.name == "*" || name_matches(self.name, &e.name)
+        });
+        match self.position {
+            Some(position) => matched.skip(position - 1).take(1).collect(),
+            None => matched.collect(),
+        }
+    }
+}
+
+/// Match a path name against a qualified XML name: exact, or by
+/// local name when the path name has no prefix.
+fn name_matches(pattern: &str, name: &str) -> bool {
+    pattern == name
+        || (!pattern.contains(':')
+            && name
+                .rsplit_once(':')
+                .is_some_and(|(_, local)| local == pattern))
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skip to just past `end`.
+    fn skip_past(&mut self, end: &str) -> Result<()> {
+        let offset = self.rest().find(end).ok_or_else(|| {
+            anyhow!(
+                "unterminated markup at byte {} (missing '{end}')",
+                self.pos
+            )
+        })?;
+        self.pos += offset + end.len();
+        Ok(())
+    }
+
+    /// Skip whitespace, the XML declaration, comments, processing
+    /// instructions and `DOCTYPE` outside the root element.
+    fn skip_misc(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "/>=".contains(c))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            bail!("expected a name at byte {}", self.pos);
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn element(&mut self) -> Result<Element> {
+        self.pos += 1; // '<'
+        let name = self.name()?.to_string();
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if let Some(after) = rest.strip_prefix("/>") {
+                self.pos = self.text.len() - after.len();
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children: Vec::new(),
+                });
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?.to_string();
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                bail!("expected '=' after attribute '{key}'");
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')
+                .ok_or_else(|| {
+                    anyhow!("attribute '{key}' is not quoted")
+                })?;
+            self.pos += 1;
+            let end = self.rest().find(quote).ok_or_else(|| {
+                anyhow!("unterminated value for attribute '{key}'")
+            })?;
+            let value = decode_entities(&self.rest()[..end])?;
+            self.pos += end + 1;
+            attributes.push((key, value));
+        }
+
+        let mut children = Vec::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                bail!("element '{name}' is not closed");
+            }
+            if let Some(after) = rest.strip_prefix("</") {
+                self.pos = self.text.len() - after.len();
+                let closing = self.name()?;
+                if closing != name {
+                    bail!("expected '</{name}>' but found '</{closing}>'");
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    bail!("malformed closing tag for '{name}'");
+                }
+                self.pos += 1;
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children,
+                });
+            }
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+                let end = after
+                    .find("]]>")
+                    .ok_or_else(|| anyhow!("unterminated CDATA section"))?;
+                children.push(Node::Text(after[..end].to_string()));
+                self.pos = self.text.len() - after.len() + end + 3;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                children.push(Node::Element(self.element()?));
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                children.push(Node::Text(decode_entities(&rest[..end])?));
+                self.pos += end;
+            }
+        }
+    }
+}
+
+/// Replace the predefined and numeric character entities.
+fn decode_entities(text: &str) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| anyhow!("unterminated entity in '{text}'"))?;
+        let entity = &rest[start + 1..start + end];
+        let decoded = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32)
+                .ok_or_else(|| anyhow!("unknown entity '&{entity};'"))?,
+        };
+        out.push(decoded);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- response -->
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <GetUserResponse status="ok">
+      <User id="7"><Name>Ada &amp; co</Name></User>
+      <User id="8"><Name><![CDATA[<Bob>]]></Name></User>
+    </GetUserResponse>
+  </soap:Body>
+</soap:Envelope>"#;
+
+    #[test]
+    fn test_select_paths() {
+        let root = parse(SOAP).unwrap();
+        let get = |path| select(&root, path).unwrap();
+        assert_eq!(
+            get("/Envelope/Body/GetUserResponse/@status").as_deref(),
+            Some("ok")
+        );
+        assert_eq!(
+            get("/soap:Envelope/soap:Body/*/User/Name").as_deref(),
+            Some("Ada & co")
+        );
+        assert_eq!(get("//User[2]/Name/text()").as_deref(), Some("<Bob>"));
+        assert_eq!(get("//User[2]/@id").as_deref(), Some("8"));
+        assert_eq!(get("//User[3]"), None);
+        assert_eq!(get("/Body"), None);
+        assert!(select(&root, "/Envelope//User").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_documents() {
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a x=1/>").is_err());
+        assert!(parse("{\"json\": true}").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert_eq!(parse("<a>&#65;&#x42;</a>").unwrap().text(), "AB");
+    }
+}
@@ -1,7 +1,23 @@
-use std::{fs, path::PathBuf};
-
-use axum::{http::StatusCode, routing::get, Json, Router};
-use muon::{DefaultTestRunner, TestRunner, TestScenario};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use axum::{
+    body::Bytes,
+    extract::Query,
+    http::{header, HeaderMap, StatusCode},
+    response::AppendHeaders,
+    routing::{get, post},
+    Json, Router,
+};
+use muon::{DefaultTestRunner, RunOptions, TestRunner, TestScenario};
 use serde_json::json;
 use tokio::task::JoinHandle;
 
@@ -18,6 +34,9 @@ impl TestServer {
             .expect("failed to bind ephemeral port");
         let addr = listener.local_addr().unwrap();
 
+        let flaky_calls = Arc::new(AtomicUsize::new(0));
+        let poll_calls = Arc::new(AtomicUsize::new(0));
+
         let app = Router::new()
             .route(
                 "/sample",
@@ -103,6 +122,173 @@ impl TestServer {
                     )
                 }),
             )
+            .route(
+                "/float",
+                get(|| async move {
+                    Json(json!({
+                        "data": {
+                            "count": 1.0,
+                            "values": [2.0, 3.0]
+                        }
+                    }))
+                }),
+            )
+            .route(
+                "/multi-headers",
+                get(|| async move {
+                    (
+                        AppendHeaders([
+                            (header::SET_COOKIE, "session=abc"),
+                            (header::SET_COOKIE, "theme=dark"),
+                            (header::VARY, "Accept"),
+                            (header::VARY, "Origin"),
+                        ]),
+                        "ok",
+                    )
+                }),
+            )
+            .route(
+                "/slow",
+                get(|| async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(
+                        20,
+                    ))
+                    .await;
+                    "slow"
+                }),
+            )
+            .route("/decompress", post(decompress_echo))
+            .route("/echo-body", post(echo_body))
+            .route(
+                "/very-slow",
+                get(|| async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(
+                        1500,
+                    ))
+                    .await;
+                    "very slow"
+                }),
+            )
+            .route(
+                "/encoded",
+                get(|| async move {
+                    Json(json!({
+                        "payload": "{ \"b\": [1, 2],\n  \"a\": \"x\" }",
+                        "count": 5,
+                        "broken": "{not json"
+                    }))
+                }),
+            )
+            .route("/echo-headers", get(echo_headers))
+            .route(
+                "/rate-limited",
+                get(|| async move {
+                    (
+                        [
+                            ("x-ratelimit-limit", "100"),
+                            ("x-ratelimit-remaining", "42"),
+                            ("ratelimit-remaining", "1"),
+                        ],
+                        "ok",
+                    )
+                }),
+            )
+            .route(
+                "/ratio",
+                get(|| async move {
+                    Json(json!({
+                        "data": {"total": 0.1 + 0.2, "label": "sum"}
+                    }))
+                }),
+            )
+            .route(
+                "/flaky",
+                get(move || async move {
+                    // Fails twice, then succeeds
+                    if flaky_calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                        (StatusCode::INTERNAL_SERVER_ERROR, "not yet")
+                    } else {
+                        (StatusCode::OK, "ready")
+                    }
+                }),
+            )
+            .route(
+                "/poll",
+                get(move || async move {
+                    let calls = poll_calls.fetch_add(1, Ordering::SeqCst) + 1;
+                    Json(json!({"calls": calls, "state": "pending"}))
+                }),
+            )
+            .route(
+                "/api/v2/sample",
+                get(|| async move {
+                    Json(json!({"data": {"kind": "versioned"}}))
+                }),
+            )
+            .route(
+                "/items",
+                get(|| async move {
+                    Json(json!({
+                        "data": {
+                            "items": [
+                                {"status": "ok", "n": 1},
+                                {"status": "ok", "n": 2},
+                                {"status": "ok"}
+                            ]
+                        }
+                    }))
+                }),
+            )
+            .route(
+                "/nullable",
+                get(|| async move {
+                    Json(json!({"data": {"id": 7, "deleted_at": null}}))
+                }),
+            )
+            .route(
+                "/xml",
+                get(|| async move {
+                    (
+                        [(header::CONTENT_TYPE, "application/xml")],
+                        r#"<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <GetUserResponse status="ok">
+      <User id="7"><Name>Ada</Name></User>
+    </GetUserResponse>
+  </soap:Body>
+</soap:Envelope>"#,
+                    )
+                }),
+            )
+            .route(
+                "/csv",
+                get(|| async move {
+                    (
+                        [(header::CONTENT_TYPE, "text/csv")],
+                        "id,name,email\r\n7,Ada,ada@example.com\r\n\
+                         8,\"Lovelace, Ada\",\r\n",
+                    )
+                }),
+            )
+            .route(
+                "/echo-query",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    Json(json!(query))
+                }),
+            )
+            .route(
+                "/location",
+                get(|| async move {
+                    (
+                        StatusCode::CREATED,
+                        [(header::LOCATION, "/users/123")],
+                        Json(json!({
+                            "data": {"url": "https://example.com/items/abc"}
+                        })),
+                    )
+                }),
+            )
             .route(
                 "/created",
                 get(|| async move {
@@ -160,6 +346,64 @@ impl Drop for TestServer {
     }
 }
 
+/// Echo the request headers back as a JSON object.
+async fn echo_headers(headers: HeaderMap) -> Json<serde_json::Value> {
+    let map: serde_json::Map<String, serde_json::Value> = headers
+        .iter()
+        .map(|(k, v)| {
+            (k.to_string(), json!(v.to_str().unwrap_or_default()))
+        })
+        .collect();
+    Json(serde_json::Value::Object(map))
+}
+
+/// Echo the raw request body with its `Content-Type`.
+async fn echo_body(
+    headers: HeaderMap,
+    body: Bytes,
+) -> Json<serde_json::Value> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    Json(json!({
+        "content_type": content_type,
+        "body": String::from_utf8_lossy(&body),
+    }))
+}
+
+/// Decode a gzip/deflate request body and echo it back with the
+/// received encoding headers.
+async fn decompress_echo(
+    headers: HeaderMap,
+    body: Bytes,
+) -> Json<serde_json::Value> {
+    let header_value = |name: header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let encoding = header_value(header::CONTENT_ENCODING);
+    let mut decoded = String::new();
+    match encoding.as_str() {
+        "gzip" => flate2::read::GzDecoder::new(&body[..])
+            .read_to_string(&mut decoded)
+            .expect("invalid gzip body"),
+        "deflate" => flate2::read::ZlibDecoder::new(&body[..])
+            .read_to_string(&mut decoded)
+            .expect("invalid deflate body"),
+        _ => panic!("unexpected content-encoding: {encoding}"),
+    };
+    Json(json!({
+        "content_encoding": encoding,
+        "content_type": header_value(header::CONTENT_TYPE),
+        "body": serde_json::from_str::<serde_json::Value>(&decoded)
+            .expect("decoded body is not JSON"),
+    }))
+}
+
 fn load_scenario(path: &str, base_url: &str) -> TestScenario {
     let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let scenario_path = manifest_dir.join("tests/fixtures").join(path);
@@ -306,6 +550,29 @@ async fn headers_succeed_when_values_match() {
     server.shutdown().await;
 }
 
+#[tokio::test]
+async fn headers_match_any_value_of_repeated_header() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("headers_multi_value.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for multi-value headers");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.steps.iter().find_map(|s| s.error.clone())
+    );
+    let response = result.steps[0].response.as_ref().unwrap();
+    assert_eq!(response.headers["set-cookie"], "session=abc, theme=dark");
+
+    server.shutdown().await;
+}
+
 #[tokio::test]
 async fn json_match_succeeds_for_exact_values() {
     let server = TestServer::spawn().await;
@@ -476,23 +743,21 @@ async fn status_mismatch_produces_failure() {
     server.shutdown().await;
 }
 
-// ── Markdown scenario tests ──────────────────────────
-
 #[tokio::test]
-async fn markdown_json_match_succeeds() {
+async fn json_wildcard_path_checks_every_element() {
     let server = TestServer::spawn().await;
     let scenario =
-        load_scenario("json_match_success.scenario.md", &server.base_url);
+        load_scenario("json_wildcard_success.yaml", &server.base_url);
     let runner = DefaultTestRunner::new();
 
     let result = runner
         .run(&scenario)
         .await
-        .expect("runner returned error for markdown json match");
+        .expect("runner returned error for json wildcard scenario");
 
     assert!(
         result.success,
-        "markdown scenario should succeed: {:?}",
+        "scenario should succeed: {:?}",
         result.error
     );
 
@@ -500,97 +765,2445 @@ async fn markdown_json_match_succeeds() {
 }
 
 #[tokio::test]
-async fn markdown_multi_step_succeeds() {
+async fn json_wildcard_path_reports_first_failing_element() {
     let server = TestServer::spawn().await;
     let scenario =
-        load_scenario("multi_step_markdown.scenario.md", &server.base_url);
+        load_scenario("json_wildcard_failure.yaml", &server.base_url);
     let runner = DefaultTestRunner::new();
 
     let result = runner
         .run(&scenario)
         .await
-        .expect("runner returned error for multi-step markdown");
+        .expect("runner returned error for json wildcard scenario");
+
+    assert!(!result.success, "scenario should fail");
+    assert_error_contains(&result, "要素 'data.items.1.n'");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_match_numeric_loose_ignores_float_representation() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("json_numeric_loose.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for numeric_loose scenario");
 
     assert!(
         result.success,
-        "multi-step markdown scenario should succeed: {:?}",
+        "scenario should succeed: {:?}",
         result.error
     );
-    assert_eq!(
-        result.steps.len(),
-        3,
-        "expected 3 steps from 3 yaml scenario blocks"
-    );
 
     server.shutdown().await;
 }
 
 #[tokio::test]
-async fn markdown_status_mismatch_produces_failure() {
+async fn json_match_is_strict_about_numbers_by_default() {
     let server = TestServer::spawn().await;
-    let scenario = load_scenario(
-        "markdown_status_mismatch.scenario.md",
-        &server.base_url,
+    let scenario =
+        load_scenario("json_numeric_strict.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for numeric strict scenario");
+
+    assert!(!result.success, "scenario should fail");
+    assert_error_contains(&result, "値が期待値と一致しません");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn expect_match_any_passes_when_one_group_passes() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("expect_match_any.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for match any scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
     );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn expect_match_any_fails_when_all_groups_fail() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("expect_match_any_all_fail.yaml", &server.base_url);
     let runner = DefaultTestRunner::new();
 
     let result = runner
         .run(&scenario)
         .await
-        .expect("runner returned error for markdown status mismatch");
+        .expect("runner returned error for match any scenario");
 
-    assert!(!result.success, "markdown scenario should fail");
+    assert!(!result.success, "scenario should fail");
     assert_error_contains(
         &result,
-        "ステータスコードが期待値と一致しません",
+        "どの期待値グループも成立しませんでした",
     );
+    assert_error_contains(&result, "含まれていません");
+    assert_error_contains(&result, "値が期待値と一致しません");
 
     server.shutdown().await;
 }
 
-// ── CEL test: expression tests ────────────────────────
-
 #[tokio::test]
-async fn test_expression_succeeds() {
+async fn save_and_bind_expand_templated_variable_names() {
     let server = TestServer::spawn().await;
-    let scenario = load_scenario("test_expression.yaml", &server.base_url);
+    let scenario =
+        load_scenario("save_templated_names.yaml", &server.base_url);
     let runner = DefaultTestRunner::new();
 
     let result = runner
         .run(&scenario)
         .await
-        .expect("runner returned error for test expression");
+        .expect("runner returned error for templated save scenario");
 
     assert!(
         result.success,
-        "CEL test expression scenario should succeed: {:?}",
+        "scenario should succeed: {:?}",
         result.error
     );
-    assert_eq!(result.steps.len(), 2);
+    for i in 0..3 {
+        assert_eq!(result.outputs[&format!("kind_{i}")], json!("list"));
+        assert_eq!(
+            result.outputs[&format!("first_id_{i}")],
+            json!("item-1")
+        );
+    }
+    assert!(!result.outputs.contains_key("i"));
+    assert_eq!(result.steps[0].attempts, 3);
 
     server.shutdown().await;
 }
 
-// ── runn runbook import tests ─────────────────────────
+#[tokio::test]
+async fn loop_until_never_met_fails_without_extra_request() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("loop_until_not_met.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for loop until scenario");
+
+    assert!(!result.success, "scenario should fail");
+    let step = &result.steps[0];
+    assert_eq!(
+        step.error.as_deref(),
+        Some("until condition not met after 3 iterations")
+    );
+    let body = step.response.as_ref().and_then(|r| r.body.as_deref());
+    let body: serde_json::Value =
+        serde_json::from_str(body.expect("missing response body")).unwrap();
+    // Exactly `count` requests: the last iteration is kept as is
+    assert_eq!(body["calls"], json!(3));
+    assert_eq!(step.attempts, 3);
+
+    server.shutdown().await;
+}
 
 #[tokio::test]
-async fn runn_runbook_basic_succeeds() {
+async fn loop_until_and_timing_failures_are_both_reported() {
     let server = TestServer::spawn().await;
     let scenario =
-        load_scenario("runn_basic.runbook.yml", &server.base_url);
+        load_scenario("loop_until_timing.yaml", &server.base_url);
     let runner = DefaultTestRunner::new();
 
     let result = runner
         .run(&scenario)
         .await
-        .expect("runner returned error for runn runbook");
+        .expect("runner returned error for loop until timing scenario");
+
+    let error = result.steps[0].error.as_deref().unwrap_or_default();
+    assert!(
+        error.starts_with("until condition not met after 2 iterations; "),
+        "{error}"
+    );
+    assert!(error.contains("loop_timing: p50"), "{error}");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn loop_timing_runs_every_iteration_within_limit() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("loop_timing.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for loop timing scenario");
 
     assert!(
         result.success,
-        "runn runbook scenario should succeed: {:?}",
+        "scenario should succeed: {:?}",
         result.error
     );
-    assert_eq!(result.steps.len(), 2);
+    // Every iteration ran even though the first one succeeded.
+    assert!(result.outputs.contains_key("kind_3"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn loop_timing_reports_exceeded_percentile() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("loop_timing_exceeded.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for loop timing scenario");
+
+    assert!(!result.success, "scenario should fail");
+    assert_error_contains(&result, "loop_timing: p50");
+    assert_error_contains(&result, "上限 1ms を超えました (2 回の反復)");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn request_body_is_compressed() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("compressed_body.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for compressed body scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn step_vars_do_not_leak_to_next_step() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("step_vars.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for step vars scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(result.outputs["path"], json!("/text"));
+    assert!(!result.outputs.contains_key("expected_kind"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn dump_vars_on_failure_snapshots_first_failing_step() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("dump_vars_on_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new().with_dump_vars_on_failure(true);
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for dump vars scenario");
+
+    assert!(!result.success, "scenario should fail");
+    assert!(result.steps[0].vars_snapshot.is_none());
+    let snapshot = result.steps[1]
+        .vars_snapshot
+        .as_ref()
+        .expect("first failing step should have a snapshot");
+    assert_eq!(snapshot["kind"], json!("list"));
+    assert_eq!(snapshot["api_token"], json!(muon::redact::REDACTED));
+    assert!(!snapshot.contains_key("steps"));
+    assert!(result.steps[2].vars_snapshot.is_none());
+
+    let without = DefaultTestRunner::new()
+        .run(&scenario)
+        .await
+        .expect("runner returned error for dump vars scenario");
+    assert!(without.steps.iter().all(|s| s.vars_snapshot.is_none()));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn dump_vars_snapshots_every_step() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("dump_vars_on_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new().with_dump_vars(true);
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for dump vars scenario");
+
+    assert!(!result.success, "scenario should fail");
+    assert!(result.steps.iter().all(|s| s.vars_snapshot.is_some()));
+    let first = result.steps[0].vars_snapshot.as_ref().unwrap();
+    assert_eq!(first["kind"], json!("list"));
+    assert_eq!(first["api_token"], json!(muon::redact::REDACTED));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn save_regex_extracts_first_capture_group() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("save_regex.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for save_regex scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(result.outputs["user_id"], json!("123"));
+    assert_eq!(result.outputs["item_id"], json!("abc"));
+    // A non-matching pattern only warns, like other saves.
+    assert!(!result.outputs.contains_key("missing"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn expect_ok_accepts_any_2xx_status() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("expect_ok.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for expect ok scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn expect_ok_is_overridden_by_explicit_status() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("expect_ok_status_wins.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for expect ok scenario");
+
+    assert!(!result.success, "scenario should fail");
+    assert_error_contains(&result, "期待: 200, 実際: 201");
+
+    server.shutdown().await;
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: std::sync::Mutex<Vec<(String, String, bool)>>,
+}
+
+impl muon::StepObserver for RecordingObserver {
+    fn on_step(&self, scenario: &TestScenario, step: &muon::StepResult) {
+        self.events.lock().unwrap().push((
+            scenario.name.clone(),
+            step.name.clone(),
+            step.success,
+        ));
+    }
+}
+
+#[tokio::test]
+async fn observer_is_notified_for_each_step() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("step_vars.yaml", &server.base_url);
+    let observer = std::sync::Arc::new(RecordingObserver::default());
+    let runner = DefaultTestRunner::new().with_observer(observer.clone());
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for observer scenario");
+
+    assert!(result.success, "scenario should succeed");
+    let events = observer.events.lock().unwrap().clone();
+    let steps: Vec<&str> = events.iter().map(|e| e.1.as_str()).collect();
+    assert_eq!(
+        steps,
+        [
+            "Step var overrides scenario var",
+            "Scenario var is restored afterwards"
+        ]
+    );
+    assert!(events.iter().all(|e| e.0 == scenario.name && e.2));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn config_headers_resolve_vars_bound_in_earlier_steps() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("config_headers_late_binding.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for late binding scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(
+        result.steps[1].request.headers["Authorization"],
+        "Bearer item-2"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn request_info_records_expanded_query() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("query_capture.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for query capture scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    let query = &result.steps[0].request.query;
+    assert_eq!(query["page"], "2");
+    assert_eq!(query["sort"], "name");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn nested_placeholders_resolve_in_request_url() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("nested_placeholders.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for nested placeholders scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert!(
+        result.steps[1]
+            .request
+            .url
+            .ends_with("?item=item-2&sort=name"),
+        "{}",
+        result.steps[1].request.url
+    );
+    assert_eq!(
+        result.steps[2].request.body.as_deref(),
+        Some(
+            r#"{"count":200,"filter":{"sort":"name"},"item":{"id":"item-2"}}"#
+        )
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn inject_request_id_sets_header_and_variable() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("inject_request_id.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for request id scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert!(result.steps.iter().all(|s| s.attempts == 1));
+    let sent = &result.steps[0].request.headers["X-Request-Id"];
+    assert_eq!(sent.len(), 36, "expected a UUID, got {sent}");
+    assert_eq!(result.outputs["request_id"], json!(sent));
+    assert!(!result.steps[1].request.headers.contains_key("X-Request-Id"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn default_accept_applies_unless_step_sets_accept() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("default_accept.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for default accept scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(
+        result.steps[0].request.headers["Accept"],
+        "application/json"
+    );
+    assert!(!result.steps[1].request.headers.contains_key("Accept"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn base_path_composes_with_base_url() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("base_path.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for base path scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(
+        result.steps[0].request.url,
+        format!("{}/api/v2/sample", server.base_url)
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn contains_accepts_literal_and_regex_entries() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("contains_regex.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for contains regex scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn contains_regex_mismatch_fails_step() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("contains_regex_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for contains regex failure");
+
+    assert!(!result.success, "non-matching regex should fail");
+    let error = result.steps[0].error.as_deref().unwrap_or_default();
+    assert!(error.contains("re:^\\d+$"), "unexpected error: {error}");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn scenario_assert_passes_cross_step_invariant() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("scenario_assert_success.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for scenario assert");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn scenario_assert_fails_even_when_steps_pass() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("scenario_assert_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for scenario assert failure");
+
+    assert!(!result.success, "falsy assert should fail the scenario");
+    assert!(result.steps.iter().all(|s| s.success));
+    let error = result.error.as_deref().unwrap_or_default();
+    assert!(
+        error.starts_with("scenario assert failed:"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn unmet_condition_reports_skipped_step() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("condition_skip.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for condition skip scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(result.steps.len(), 2);
+    assert!(!result.steps[0].skipped);
+    let skipped = &result.steps[1];
+    assert!(skipped.skipped && skipped.success);
+    assert!(skipped.response.is_none());
+    assert_eq!(
+        skipped.skip_reason.as_deref(),
+        Some("condition not met: {{ feature_enabled }}")
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn expect_http_version_matches_negotiated_protocol() {
+    let server = TestServer::spawn().await;
+    let runner = DefaultTestRunner::new();
+
+    let scenario = load_scenario("http_version.yaml", &server.base_url);
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for http_version scenario");
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    let response = result.steps[0].response.as_ref().unwrap();
+    assert_eq!(response.http_version.as_deref(), Some("HTTP/1.1"));
+
+    let scenario =
+        load_scenario("http_version_mismatch.yaml", &server.base_url);
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for http_version mismatch");
+    assert!(!result.success, "HTTP/2 expectation should fail");
+    let error = result.steps[0].error.as_deref().unwrap_or_default();
+    assert!(
+        error.contains("期待: HTTP/2, 実際: HTTP/1.1"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_in_accepts_allowed_values() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("json_in.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_in scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_in_rejects_value_outside_allowed_set() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("json_in_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_in failure scenario");
+
+    assert!(!result.success, "disallowed value should fail");
+    let error = result.steps[0].error.as_deref().unwrap_or_default();
+    assert!(error.contains("data.items.0.status"), "error: {error}");
+    assert!(error.contains("\"active\""), "error: {error}");
+    assert!(error.contains("\"ok\""), "error: {error}");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn run_suite_aggregates_directory_results() {
+    let server = TestServer::spawn().await;
+    let fixtures =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["contains_success.yaml", "status_mismatch.yaml"] {
+        fs::copy(fixtures.join(name), dir.path().join(name)).unwrap();
+    }
+    let options = RunOptions {
+        base_url: Some(server.base_url.clone()),
+        concurrency: 2,
+        ..Default::default()
+    };
+
+    let report = muon::run_suite(&[dir.path().to_path_buf()], options)
+        .await
+        .expect("run_suite returned error");
+
+    assert_eq!(report.scenarios.len(), 2);
+    let passed = report.scenarios.iter().filter(|s| s.success).count();
+    assert_eq!(passed, 1, "one fixture passes and one fails");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn run_suite_fail_fast_skips_remaining_scenarios() {
+    let server = TestServer::spawn().await;
+    let fixtures =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let paths = [
+        fixtures.join("status_mismatch.yaml"),
+        fixtures.join("contains_success.yaml"),
+    ];
+    let options = RunOptions {
+        base_url: Some(server.base_url.clone()),
+        fail_fast: true,
+        ..Default::default()
+    };
+
+    let report = muon::run_suite(&paths, options)
+        .await
+        .expect("run_suite returned error");
+
+    assert_eq!(report.scenarios.len(), 1, "second scenario is not run");
+    assert!(!report.scenarios[0].success);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn events_ndjson_on_stdout_is_pure_json_lines() {
+    let server = TestServer::spawn().await;
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/condition_skip.yaml");
+    let content = fs::read_to_string(&fixture)
+        .expect("failed to read fixture")
+        .replace("__BASE_URL__", &server.base_url);
+    let path = dir.path().join("events.yaml");
+    fs::write(&path, content).expect("failed to write scenario");
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_muon"))
+        .arg("--path")
+        .arg(&path)
+        .args(["--events-ndjson", "-"])
+        .env_remove("RUST_LOG")
+        .output()
+        .await
+        .expect("failed to run muon");
+
+    let stdout = String::from_utf8(output.stdout).expect("stdout is UTF-8");
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| {
+            serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("not JSON ({e}): {line}"))
+        })
+        .collect();
+    assert_eq!(events.len(), 2, "{events:?}");
+    assert_eq!(events[0]["skipped"], json!(false));
+    assert_eq!(events[1]["skipped"], json!(true));
+    assert_eq!(
+        events[1]["skip_reason"],
+        json!("condition not met: {{ feature_enabled }}")
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn var_short_flag_overrides_scenario_vars() {
+    let server = TestServer::spawn().await;
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/condition_skip.yaml");
+    let content = fs::read_to_string(&fixture)
+        .expect("failed to read fixture")
+        .replace("__BASE_URL__", &server.base_url);
+    let path = dir.path().join("vars.yaml");
+    fs::write(&path, content).expect("failed to write scenario");
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_muon"))
+        .arg("--path")
+        .arg(&path)
+        .args(["-V", "feature_enabled=true", "--events-ndjson", "-"])
+        .env_remove("RUST_LOG")
+        .output()
+        .await
+        .expect("failed to run muon");
+
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).expect("stdout is UTF-8");
+    let last: serde_json::Value =
+        serde_json::from_str(stdout.lines().last().unwrap_or_default())
+            .expect("last event is JSON");
+    assert_eq!(last["skipped"], json!(false), "{stdout}");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn list_tags_subcommand_counts_scenarios_per_tag() {
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    for (file, tags) in
+        [("a.yaml", "[smoke, users]"), ("b.yaml", "[smoke]")]
+    {
+        fs::write(
+            dir.path().join(file),
+            format!("name: {file}\ntags: {tags}\nsteps: []\n"),
+        )
+        .expect("failed to write scenario");
+    }
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_muon"))
+        .args(["list-tags", "--output", "json", "--path"])
+        .arg(dir.path())
+        .env_remove("RUST_LOG")
+        .output()
+        .await
+        .expect("failed to run muon");
+
+    assert!(output.status.success(), "{output:?}");
+    let counts: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is JSON");
+    assert_eq!(counts, json!({"smoke": 2, "users": 1}));
+}
+
+#[tokio::test]
+async fn form_body_is_urlencoded() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("form_body.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for form body scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn body_file_resolves_against_the_scenario_directory() {
+    let server = TestServer::spawn().await;
+    let mut scenario = load_scenario("body_file.yaml", &server.base_url);
+    scenario.config.base_dir = Some(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures"),
+    );
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for body file scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn multipart_body_sends_text_and_file_parts() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("multipart_body.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for multipart body scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.steps.first().and_then(|s| s.error.clone())
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_decoded_compares_embedded_json_structurally() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("json_decoded.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_decoded scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.steps.first().and_then(|s| s.error.clone())
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_decoded_reports_inner_parse_failure() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("json_decoded_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_decoded failure");
+
+    assert!(!result.success, "unparseable inner JSON should fail");
+    let error = result.steps[0].error.as_deref().unwrap_or_default();
+    assert!(
+        error.contains("JSONパス 'broken' の値が有効なJSONではありません"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn config_auth_bearer_uses_bound_token() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("auth_bearer.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for bearer auth scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.steps.iter().find_map(|s| s.error.clone())
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn config_auth_basic_encodes_credentials() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("auth_basic.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for basic auth scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.steps.iter().find_map(|s| s.error.clone())
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn step_timeout_overrides_config_timeout() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("step_timeout.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for step timeout scenario");
+
+    assert_eq!(result.steps.len(), 2);
+    assert!(
+        result.steps[0].success,
+        "step timeout should win: {:?}",
+        result.steps[0].error
+    );
+    assert!(
+        !result.steps[1].success,
+        "config timeout should apply when the step timeout is 0"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn shape_accepts_matching_structure() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("shape.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for shape scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.steps.first().and_then(|s| s.error.clone())
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn shape_reports_each_violated_path() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("shape_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for shape failure scenario");
+
+    assert!(!result.success, "shape violations should fail");
+    let error = result.steps[0].error.as_deref().unwrap_or_default();
+    assert!(
+        error.contains("必須パス 'data.total' が存在しません"),
+        "unexpected error: {error}"
+    );
+    assert!(
+        error.contains("禁止パス 'data.items.0.n' が存在します"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn expect_cert_fails_without_peer_certificate() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("cert_plain_http.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for cert scenario");
+
+    assert!(!result.success, "plain HTTP cannot satisfy expect.cert");
+    let step = &result.steps[0];
+    assert!(step.response.as_ref().unwrap().certificate.is_none());
+    let error = step.error.as_deref().unwrap_or_default();
+    assert!(
+        error.contains("サーバー証明書を取得できません"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn step_retry_reruns_failed_step_until_success() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("step_retry.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for step retry scenario");
+
+    assert!(result.success, "retry should succeed: {:?}", result.steps);
+    assert_eq!(result.steps.len(), 2);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn step_retry_fails_after_exhausting_retries() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("step_retry_exhausted.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for exhausted retry scenario");
+
+    assert!(!result.success);
+    assert_eq!(result.steps.len(), 1);
+    assert!(
+        result.steps[0]
+            .error
+            .as_deref()
+            .unwrap_or("")
+            .contains("500"),
+        "unexpected error: {:?}",
+        result.steps[0].error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn matches_saves_named_captures() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("body_matches.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for matches scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.steps
+    );
+    assert_eq!(result.outputs["who"], json!("runner"));
+    assert_eq!(result.outputs["last"], json!("world"));
+    // Only the listed groups are saved when names are given.
+    assert!(!result.outputs.contains_key("greeting"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn matches_fails_when_body_does_not_match() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("body_matches_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for matches failure scenario");
+
+    assert!(!result.success);
+    let error = result.steps[0].error.as_deref().unwrap_or("");
+    assert!(
+        error.contains("レスポンスボディが '^Goodbye"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn run_soak_repeats_weighted_scenarios_until_deadline() {
+    let server = TestServer::spawn().await;
+    let picked = load_scenario("contains_success.yaml", &server.base_url);
+    let mut never = load_scenario("status_mismatch.yaml", &server.base_url);
+    never.weight = Some(0);
+    let options = RunOptions {
+        concurrency: 2,
+        ..Default::default()
+    };
+
+    let report = muon::soak::run_soak(
+        vec![picked.clone(), never],
+        options,
+        std::time::Duration::from_millis(300),
+        42,
+    )
+    .await
+    .expect("run_soak returned error");
+
+    assert_eq!(report.seed, 42);
+    assert!(report.iterations > 2, "expected repeated runs: {report:?}");
+    assert_eq!(report.failed, 0, "zero-weight scenario must not run");
+    assert_eq!(report.scenarios.len(), 1);
+    assert_eq!(report.scenarios[&picked.name].passed, report.iterations);
+    assert!(report.latency.is_some());
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn schema_accepts_matching_body() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("json_schema.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for schema scenario");
+
+    assert!(result.success, "schema should pass: {:?}", result.steps);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn schema_reports_every_violation() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("json_schema_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for schema failure scenario");
+
+    assert!(!result.success);
+    let error = result.steps[0].error.as_deref().unwrap_or("");
+    assert!(
+        error.contains("schema: /data:"),
+        "unexpected error: {error}"
+    );
+    assert!(
+        error.contains("schema: /data/kind:"),
+        "unexpected error: {error}"
+    );
+    let error = result.steps[1].error.as_deref().unwrap_or("");
+    assert!(
+        error.contains("有効なJSONではありません"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn expectations_use_selected_environment_vars() {
+    let server = TestServer::spawn().await;
+    let runner = DefaultTestRunner::new();
+
+    for (env, should_pass) in
+        [("dev", true), ("staging", true), ("broken", false)]
+    {
+        let options = RunOptions {
+            environment: Some(env.to_string()),
+            ..Default::default()
+        };
+        let scenario = options
+            .prepare(vec![load_scenario(
+                "env_expected.yaml",
+                &server.base_url,
+            )])
+            .expect("environment should be defined")
+            .remove(0);
+
+        let result = runner
+            .run(&scenario)
+            .await
+            .expect("runner returned error for environment scenario");
+        assert_eq!(
+            result.success, should_pass,
+            "env {env}: {:?}",
+            result.steps
+        );
+    }
+
+    let options = RunOptions {
+        environment: Some("prod".to_string()),
+        ..Default::default()
+    };
+    let err = options
+        .prepare(vec![load_scenario("env_expected.yaml", &server.base_url)])
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("no environment 'prod'"),
+        "unexpected error: {err}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_approx_accepts_values_within_epsilon() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("json_approx.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_approx scenario");
+
+    assert!(result.success, "approx should pass: {:?}", result.steps);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_approx_rejects_out_of_tolerance_and_non_numbers() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("json_approx_failure.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_approx failure scenario");
+
+    assert!(!result.success);
+    let error = result.steps[0].error.as_deref().unwrap_or("");
+    assert!(error.contains("期待: 0.31"), "unexpected error: {error}");
+    let error = result.steps[1].error.as_deref().unwrap_or("");
+    assert!(
+        error.contains("'data.label' の値が数値ではありません"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn max_duration_ms_fails_slow_requests() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("max_duration.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for max duration scenario");
+
+    assert!(
+        result.steps[0].success,
+        "elapsedMs should be visible to test: {:?}",
+        result.steps[0].error
+    );
+    assert!(!result.steps[1].success);
+    let error = result.steps[1].error.as_deref().unwrap_or("");
+    assert!(
+        error.contains("max_duration_ms 5 を超えました"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn explain_records_every_assertion() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("explain.yaml", &server.base_url);
+
+    let quiet = DefaultTestRunner::new()
+        .run(&scenario)
+        .await
+        .expect("runner returned error for explain scenario");
+    assert!(quiet.steps[0].assertions.is_empty());
+
+    let result = DefaultTestRunner::new()
+        .with_explain(true)
+        .run(&scenario)
+        .await
+        .expect("runner returned error for explain scenario");
+    assert!(!result.success);
+
+    let assertions = &result.steps[0].assertions;
+    let find = |check: &str| {
+        assertions
+            .iter()
+            .find(|a| a.check == check)
+            .unwrap_or_else(|| panic!("missing {check}: {assertions:?}"))
+    };
+    assert_eq!(assertions.len(), 5, "{assertions:?}");
+    assert!(find("status").passed);
+    assert!(find("json data.kind").passed);
+    assert!(find("json_lengths data.items").passed);
+    assert!(find("contains item-1").passed);
+    let failed = find("json data.map.a");
+    assert!(!failed.passed);
+    assert_eq!(failed.expected.as_deref(), Some("2"));
+    assert_eq!(failed.actual.as_deref(), Some("1"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn rate_limit_checks_limit_and_remaining_headers() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("rate_limit.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for rate limit scenario");
+
+    assert!(
+        result.steps[0].success,
+        "default headers should pass: {:?}",
+        result.steps[0].error
+    );
+    assert!(!result.steps[1].success);
+    let error = result.steps[1].error.as_deref().unwrap_or("");
+    assert!(
+        error.contains("期待: 5 以上, 実際: 1"),
+        "unexpected error: {error}"
+    );
+
+    server.shutdown().await;
+}
+
+/// Serve `ok` over HTTPS with a freshly generated self-signed
+/// certificate and return the base URL.
+async fn spawn_self_signed_https() -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::rustls::{self, pki_types::PrivateKeyDer};
+
+    let certified =
+        rcgen::generate_simple_self_signed(vec!["localhost".into()])
+            .expect("failed to generate certificate");
+    let key =
+        PrivateKeyDer::Pkcs8(certified.key_pair.serialize_der().into());
+    let config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_no_client_auth()
+    .with_single_cert(vec![certified.cert.der().clone()], key)
+    .expect("failed to build TLS config");
+    let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("failed to bind ephemeral port");
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let Ok(mut tls) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = tls.read(&mut buf).await;
+                let _ = tls
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\
+                          Connection: close\r\n\r\nok",
+                    )
+                    .await;
+                let _ = tls.shutdown().await;
+            });
+        }
+    });
+    format!("https://localhost:{port}")
+}
+
+fn self_signed_scenario(
+    base_url: &str,
+    insecure_tls: bool,
+) -> TestScenario {
+    TestScenario::from_yaml(&format!(
+        r#"
+name: Self-signed staging
+config:
+  base_url: {base_url}
+  insecure_tls: {insecure_tls}
+steps:
+  - name: fetch
+    request:
+      method: GET
+      url: /
+    expect:
+      status: 200
+      contains:
+        - ok
+"#
+    ))
+    .expect("failed to parse scenario yaml")
+}
+
+#[tokio::test]
+async fn insecure_tls_accepts_self_signed_certificate() {
+    let base_url = spawn_self_signed_https().await;
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&self_signed_scenario(&base_url, true))
+        .await
+        .expect("runner returned error");
+
+    assert!(result.success, "insecure_tls should pass: {result:?}");
+}
+
+#[tokio::test]
+async fn self_signed_certificate_fails_without_insecure_tls() {
+    let base_url = spawn_self_signed_https().await;
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&self_signed_scenario(&base_url, false))
+        .await
+        .expect("runner returned error");
+
+    assert!(!result.success, "certificate should be rejected");
+}
+
+#[tokio::test]
+async fn dry_run_resolves_requests_without_sending() {
+    let scenario = TestScenario::from_yaml(
+        r#"
+name: Dry run scenario
+config:
+  base_url: http://127.0.0.1:9
+  headers:
+    Authorization: Bearer {{ token }}
+vars:
+  token: secret
+  user: alice
+steps:
+  - name: create user
+    request:
+      method: POST
+      url: /users/{{ user }}
+      query:
+        notify: "{{ user }}"
+      body:
+        name: "{{ user }}"
+    expect:
+      status: 201
+    save:
+      user_id: data.id
+  - name: unreachable if sent
+    retry:
+      count: 3
+    request:
+      method: DELETE
+      url: /users/{{ user }}
+    expect:
+      status: 204
+"#,
+    )
+    .expect("failed to parse scenario yaml");
+    let runner = DefaultTestRunner::new().with_dry_run(true);
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for dry run");
+
+    assert!(result.success, "dry run should not fail: {result:?}");
+    assert_eq!(result.steps.len(), 2);
+    let step = &result.steps[0];
+    assert!(step.skipped);
+    assert_eq!(step.skip_reason.as_deref(), Some("dry run"));
+    assert!(step.response.is_none());
+    assert_eq!(step.request.method, "Post");
+    assert_eq!(step.request.url, "http://127.0.0.1:9/users/alice");
+    assert_eq!(step.request.query["notify"], "alice");
+    assert_eq!(step.request.headers["Authorization"], "Bearer secret");
+    assert_eq!(step.request.body.as_deref(), Some(r#"{"name":"alice"}"#));
+    assert_eq!(result.steps[1].request.method, "Delete");
+}
+
+#[tokio::test]
+async fn env_placeholders_resolve_in_first_step() {
+    std::env::set_var("MUON_TEST_ENV_REGION", "eu-west");
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("env_interpolation.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for env interpolation scenario");
+
+    assert!(result.success, "scenario should succeed: {result:?}");
+    assert_eq!(result.steps[0].request.query["region"], "eu-west");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn save_reads_response_headers() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("save_header.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for save header scenario");
+
+    assert!(result.success, "scenario should succeed: {result:?}");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn save_special_paths_bind_whole_response() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("save_special.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for save special scenario");
+
+    assert!(result.success, "scenario should succeed: {result:?}");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn save_non_json_body_keeps_other_saves() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("save_non_json.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for non-JSON save scenario");
+
+    assert!(result.success, "scenario should succeed: {result:?}");
+    assert_eq!(result.outputs["raw"], json!("Hello runner world"));
+    assert_eq!(
+        result.outputs["content_type"],
+        json!("text/plain; charset=utf-8")
+    );
+    assert!(
+        !result.outputs.contains_key("user_id"),
+        "unresolvable JSON path should not be saved"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn teardown_runs_after_main_step_failure() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("lifecycle_hooks.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for lifecycle scenario");
+
+    assert!(!result.success, "main step failure should fail scenario");
+    let names: Vec<&str> =
+        result.steps.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "Seed data",
+            "Main step fails",
+            "Clean up seeded data",
+            "Cleanup check fails"
+        ]
+    );
+    assert!(result.steps[2].success, "teardown sees setup vars");
+    assert_eq!(result.outputs["cleaned_id"], json!("item-1"));
+    assert_eq!(
+        result.error.as_deref(),
+        Some("一部のステップが失敗しました")
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn teardown_failure_only_fails_scenario_when_configured() {
+    let server = TestServer::spawn().await;
+    let mut scenario =
+        load_scenario("lifecycle_hooks.yaml", &server.base_url);
+    scenario.steps[0].expect.status = Some(200.into());
+    let runner = DefaultTestRunner::new();
+
+    let result = runner.run(&scenario).await.expect("runner error");
+    assert!(result.success, "teardown failure is only reported");
+    assert!(!result.steps[3].success);
+
+    scenario.config.fail_on_teardown = true;
+    let result = runner.run(&scenario).await.expect("runner error");
+    assert!(!result.success, "fail_on_teardown should fail scenario");
+    assert_eq!(result.error.as_deref(), Some("teardown failed"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn setup_failure_skips_main_steps_but_runs_teardown() {
+    let server = TestServer::spawn().await;
+    let mut scenario =
+        load_scenario("lifecycle_hooks.yaml", &server.base_url);
+    scenario.setup[0].expect.status = Some(500.into());
+    let runner = DefaultTestRunner::new();
+
+    let result = runner.run(&scenario).await.expect("runner error");
+
+    assert!(!result.success);
+    assert_eq!(result.error.as_deref(), Some("setup failed"));
+    let names: Vec<&str> =
+        result.steps.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["Seed data", "Clean up seeded data", "Cleanup check fails"]
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn condition_evaluates_cel_expression() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("condition_cel.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for CEL condition scenario");
+
+    assert!(result.success, "scenario should succeed: {result:?}");
+    assert_eq!(result.steps.len(), 3);
+    assert!(!result.steps[1].skipped, "true condition runs the step");
+    assert!(result.steps[1].response.is_some());
+    assert!(result.steps[2].skipped, "false condition skips the step");
+    assert_eq!(
+        result.steps[2].skip_reason.as_deref(),
+        Some("condition not met: steps.login.res.status == 500")
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn condition_evaluation_error_fails_the_step() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("condition_error.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for condition error scenario");
+
+    assert!(!result.success, "scenario should fail");
+    let step = &result.steps[1];
+    assert!(!step.success);
+    assert!(!step.skipped);
+    assert!(step.response.is_none(), "no request should be sent");
+    let error = step.error.as_deref().unwrap_or_default();
+    assert!(error.starts_with("condition error: "), "{error}");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn run_scenario_yaml_applies_base_url() {
+    let server = TestServer::spawn().await;
+    let yaml = r#"
+name: Inline scenario
+config:
+  base_url: http://127.0.0.1:9
+steps:
+  - name: text
+    request:
+      method: GET
+      url: /text
+    expect:
+      status: 200
+      contains:
+        - Hello
+"#;
+
+    let result = muon::run_scenario_yaml(yaml, Some(&server.base_url))
+        .await
+        .expect("run_scenario_yaml returned error");
+
+    assert!(result.success, "scenario should succeed: {result:?}");
+    assert!(muon::run_scenario_yaml("name: [", None).await.is_err());
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn with_proxy_routes_requests_through_proxy() {
+    let server = TestServer::spawn().await;
+    // The test server answers absolute-form proxy requests itself
+    let scenario = TestScenario::from_yaml(
+        r#"
+name: Proxy scenario
+config:
+  timeout: 5
+steps:
+  - name: via proxy
+    request:
+      method: GET
+      url: http://muon-proxy-test.invalid/text
+    expect:
+      status: 200
+      contains:
+        - Hello
+"#,
+    )
+    .expect("failed to parse scenario yaml");
+    let runner = DefaultTestRunner::new()
+        .with_proxy(&server.base_url)
+        .expect("proxy URL should be valid");
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for proxy scenario");
+
+    assert!(result.success, "request should go via proxy: {result:?}");
+    assert!(DefaultTestRunner::new().with_proxy("http://[::1").is_err());
+
+    server.shutdown().await;
+}
+
+/// Serve `ok` over keep-alive HTTP/1.1 and count accepted TCP
+/// connections. Returns the base URL and the counter.
+async fn spawn_connection_counting_server() -> (String, Arc<AtomicUsize>) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("failed to bind ephemeral port");
+    let addr = listener.local_addr().unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&connections);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            counter.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async move {
+                let mut stream = BufReader::new(stream);
+                loop {
+                    // Requests are bodiless GETs: read up to the
+                    // blank line, then answer and wait for the next
+                    let mut line = String::new();
+                    loop {
+                        line.clear();
+                        match stream.read_line(&mut line).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(_) if line == "\r\n" => break,
+                            Ok(_) => {}
+                        }
+                    }
+                    let response =
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+                    if stream.get_mut().write_all(response).await.is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    (format!("http://{addr}"), connections)
+}
+
+#[tokio::test]
+async fn sequential_steps_reuse_one_connection() {
+    let (base_url, connections) = spawn_connection_counting_server().await;
+    let steps: String = (0..50)
+        .map(|i| {
+            format!(
+                "  - name: request {i}\n    request:\n      \
+                 method: GET\n      url: /ping\n    expect:\n      \
+                 status: 200\n"
+            )
+        })
+        .collect();
+    let scenario = TestScenario::from_yaml(&format!(
+        "name: Connection reuse\nconfig:\n  base_url: {base_url}\n\
+         steps:\n{steps}"
+    ))
+    .expect("failed to parse scenario yaml");
+    let runner = DefaultTestRunner::new()
+        .with_pool_config(4, Some(std::time::Duration::from_secs(30)))
+        .expect("pool config should build");
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for connection reuse scenario");
+
+    assert!(result.success, "scenario should succeed: {result:?}");
+    assert_eq!(result.steps.len(), 50);
+    assert_eq!(
+        connections.load(Ordering::SeqCst),
+        1,
+        "50 sequential requests should share one connection"
+    );
+}
+
+#[tokio::test]
+async fn json_eq_failure_records_structured_diff() {
+    let server = TestServer::spawn().await;
+    let yaml = r#"
+name: json_eq diff
+config:
+  timeout: 5
+steps:
+  - name: sample
+    request:
+      method: GET
+      url: /sample
+    expect:
+      status: 200
+      json_eq:
+        data:
+          kind: map
+          items:
+            - id: item-1
+          map: { a: 1, b: 2, c: 3 }
+"#;
+
+    let result = muon::run_scenario_yaml(yaml, Some(&server.base_url))
+        .await
+        .expect("run_scenario_yaml returned error");
+
+    assert!(!result.success);
+    let step = &result.steps[0];
+    let paths: Vec<&str> =
+        step.diff.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, ["data.items", "data.kind"], "{:?}", step.diff);
+    assert_eq!(step.diff[1].kind, muon::MismatchKind::Value);
+    assert_eq!(step.diff[1].actual, Some(json!("list")));
+    // The error string keeps the machine-readable messages
+    let error = step.error.as_deref().unwrap_or_default();
+    assert!(error.contains(
+        "data_eq 'data.kind': 値が期待値と一致しません。\
+         期待: String(\"map\"), 実際: String(\"list\")"
+    ));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_ne_fails_only_on_equal_values() {
+    let server = TestServer::spawn().await;
+    let mut scenario = load_scenario("json_ne.yaml", &server.base_url);
+    scenario.config.continue_on_failure = true;
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_ne scenario");
+
+    assert!(result.steps[0].success, "{:?}", result.steps[0].error);
+    assert!(!result.steps[1].success);
+    assert_eq!(
+        result.steps[1].error.as_deref(),
+        Some(
+            "json_ne: JSONパス 'data.items.0.id' の値が \"item-1\" と\
+             一致しています"
+        )
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn json_exists_and_absent_treat_null_as_present_but_empty() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("json_exists.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for json_exists scenario");
+
+    assert!(result.steps[0].success, "{:?}", result.steps[0].error);
+    assert_eq!(
+        result.steps[1].error.as_deref(),
+        Some("json_exists: JSONパス 'data.deleted_at' の値が null です")
+    );
+    assert_eq!(
+        result.steps[2].error.as_deref(),
+        Some(
+            "json_absent: JSONパス 'data.deleted_at' が\
+             レスポンスに存在します (実際: null)"
+        )
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn headers_contain_matches_case_insensitive_substrings() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("headers_contain.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for headers_contain scenario");
+
+    assert!(result.steps[0].success, "{:?}", result.steps[0].error);
+    assert_eq!(
+        result.steps[1].error.as_deref(),
+        Some(
+            "headers_contain: ヘッダー 'Content-Type' に \
+             'application/json' が含まれていません \
+             (実際: text/plain; charset=utf-8)"
+        )
+    );
+    assert_eq!(
+        result.steps[2].error.as_deref(),
+        Some(
+            "headers_contain: ヘッダー 'X-Missing' が\
+             レスポンスに存在しません"
+        )
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn status_accepts_lists_and_ranges() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("status_set.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for status set scenario");
+    assert!(result.success, "scenario should succeed: {result:?}");
+
+    let mut scenario = scenario;
+    scenario.steps[0].expect.status =
+        Some(muon::StatusExpectation::OneOf(vec![201, 204]));
+    let result = runner.run(&scenario).await.unwrap();
+    let error = result.steps[0].error.as_deref().unwrap_or_default();
+    assert!(error.contains("201 or 204"), "{error}");
+
+    let yaml = |status: &str| {
+        format!(
+            "name: s\nsteps:\n  - name: a\n    \
+             request: {{method: GET, url: /}}\n    \
+             expect: {{status: {status}}}\n"
+        )
+    };
+    for invalid in ["6xx", "\"204-200\"", "[]", "teapot"] {
+        assert!(
+            TestScenario::from_yaml(&yaml(invalid)).is_err(),
+            "{invalid} should be rejected"
+        );
+    }
+    let parsed = TestScenario::from_yaml(&yaml("\"4XX\"")).unwrap();
+    assert_eq!(
+        parsed.steps[0].expect.status,
+        Some(muon::StatusExpectation::Range(400, 499))
+    );
+    assert!(parsed.to_yaml().unwrap().contains("status: 4xx"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn csv_checks_rows_headers_and_cells() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("csv.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for csv scenario");
+
+    assert!(result.steps[0].success, "{:?}", result.steps[0].error);
+    assert_eq!(
+        result.steps[1].error.as_deref(),
+        Some(
+            "csv: 行数が期待値と一致しません。期待: 3, 実際: 2; \
+             csv: セル '1.name' の値が期待値と一致しません。\
+             期待: Ada, 実際: Lovelace, Ada"
+        )
+    );
+    assert_eq!(
+        result.steps[2].error.as_deref(),
+        Some("csv: セル '0.phone' がレスポンスに存在しません")
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn xpath_checks_xml_values_and_rejects_non_xml_bodies() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("xpath.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for xpath scenario");
+
+    assert!(result.steps[0].success, "{:?}", result.steps[0].error);
+    assert_eq!(
+        result.steps[1].error.as_deref(),
+        Some(
+            "xpath: パス '//User/Name' の値が期待値と一致しません。\
+             期待: Bob, 実際: Ada"
+        )
+    );
+    assert_eq!(
+        result.steps[2].error.as_deref(),
+        Some("xpath: パス '//User/Email' がレスポンスに存在しません")
+    );
+    let error = result.steps[3].error.as_deref().unwrap_or_default();
+    assert!(
+        error.starts_with("xpath: レスポンスが有効なXMLではありません"),
+        "{error}"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn missing_required_env_fails_fast() {
+    let scenario = TestScenario::from_yaml(
+        r#"
+name: Requires env scenario
+requires_env:
+  - MUON_TEST_REQUIRED_ENV_UNSET
+  - MUON_TEST_REQUIRED_ENV_ALSO_UNSET
+steps:
+  - name: never sent
+    request:
+      method: GET
+      url: http://127.0.0.1:9/unreachable
+    expect:
+      status: 200
+"#,
+    )
+    .expect("failed to parse scenario yaml");
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for requires_env scenario");
+
+    assert!(!result.success, "scenario should fail");
+    assert!(result.steps.is_empty(), "no step should be executed");
+    assert_eq!(
+        result.error.as_deref(),
+        Some(
+            "missing required env: MUON_TEST_REQUIRED_ENV_UNSET, \
+             MUON_TEST_REQUIRED_ENV_ALSO_UNSET"
+        )
+    );
+}
+
+// ── Markdown scenario tests ──────────────────────────
+
+#[tokio::test]
+async fn markdown_json_match_succeeds() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("json_match_success.scenario.md", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for markdown json match");
+
+    assert!(
+        result.success,
+        "markdown scenario should succeed: {:?}",
+        result.error
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn markdown_multi_step_succeeds() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("multi_step_markdown.scenario.md", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for multi-step markdown");
+
+    assert!(
+        result.success,
+        "multi-step markdown scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(
+        result.steps.len(),
+        3,
+        "expected 3 steps from 3 yaml scenario blocks"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn markdown_status_mismatch_produces_failure() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario(
+        "markdown_status_mismatch.scenario.md",
+        &server.base_url,
+    );
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for markdown status mismatch");
+
+    assert!(!result.success, "markdown scenario should fail");
+    assert_error_contains(
+        &result,
+        "ステータスコードが期待値と一致しません",
+    );
+
+    server.shutdown().await;
+}
+
+// ── CEL test: expression tests ────────────────────────
+
+#[tokio::test]
+async fn test_expression_body_alias() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("body_alias_expression.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for body alias scenario");
+
+    assert!(
+        result.success,
+        "scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(result.outputs["first_id"], json!("item-1"));
+    assert!(!result.outputs.contains_key("body"));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_expression_succeeds() {
+    let server = TestServer::spawn().await;
+    let scenario = load_scenario("test_expression.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for test expression");
+
+    assert!(
+        result.success,
+        "CEL test expression scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(result.steps.len(), 2);
+    assert_eq!(result.outputs["first_item"], json!("item-1"));
+    assert_eq!(result.outputs["item_count"], json!(2));
+    assert!(
+        result.outputs.keys().all(|k| k != "current"
+            && k != "previous"
+            && k != "steps"
+            && !k.starts_with("steps.")
+            && !k.starts_with("env.")),
+        "internal vars leaked into outputs: {:?}",
+        result.outputs.keys().collect::<Vec<_>>()
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_expression_asserts_raw_body_of_plaintext_response() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("raw_body_expression.yaml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for rawBody expression");
+
+    assert!(
+        result.success,
+        "rawBody test expression should succeed: {:?}",
+        result.steps.first().and_then(|s| s.error.clone())
+    );
+
+    server.shutdown().await;
+}
+
+// ── runn runbook import tests ─────────────────────────
+
+#[tokio::test]
+async fn runn_runbook_basic_succeeds() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("runn_basic.runbook.yml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for runn runbook");
+
+    assert!(
+        result.success,
+        "runn runbook scenario should succeed: {:?}",
+        result.error
+    );
+    assert_eq!(result.steps.len(), 2);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn runn_runbook_raw_body_succeeds() {
+    let server = TestServer::spawn().await;
+    let scenario =
+        load_scenario("runn_raw_body.runbook.yml", &server.base_url);
+    let runner = DefaultTestRunner::new();
+
+    let result = runner
+        .run(&scenario)
+        .await
+        .expect("runner returned error for runn rawBody runbook");
+
+    assert!(
+        result.success,
+        "runn rawBody runbook should succeed: {:?}",
+        result.steps.first().and_then(|s| s.error.clone())
+    );
+    assert_eq!(result.steps.len(), 1);
 
     server.shutdown().await;
 }